@@ -0,0 +1,121 @@
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use sha2::{Digest, Sha256};
+use time::OffsetDateTime;
+use time::format_description::well_known::Rfc3339;
+use x509_parser::certificate::X509Certificate;
+use x509_parser::extensions::GeneralName;
+use x509_parser::parse_x509_certificate;
+use x509_parser::public_key::PublicKey;
+use crate::Attributes;
+
+// A decoded X.509 certificate: its SHA-256 fingerprint (over the DER) and what it says
+pub struct Certificate {
+    pub fingerprint: String,
+    pub attrs: Attributes,
+}
+
+pub fn format_time(time: OffsetDateTime) -> String {
+    time.format(&Rfc3339).unwrap_or_default()
+}
+
+// Algorithm and size of the subject public key, e.g. "RSA 2048", "EC P-256", "Ed25519"
+fn describe_key(cert: &X509Certificate) -> String {
+    let spki = cert.public_key();
+    let curve = || {
+        let oid = spki.algorithm.parameters.as_ref().and_then(|p| p.as_oid().ok()).map(|oid| oid.to_id_string());
+        match oid.as_deref() {
+            Some("1.2.840.10045.3.1.7") => "P-256".to_string(),
+            Some("1.3.132.0.34") => "P-384".to_string(),
+            Some("1.3.132.0.35") => "P-521".to_string(),
+            Some("1.3.132.0.10") => "secp256k1".to_string(),
+            Some(other) => other.to_string(),
+            None => "unknown".to_string(),
+        }
+    };
+
+    match spki.algorithm.algorithm.to_id_string().as_str() {
+        "1.2.840.113549.1.1.1" | "1.2.840.113549.1.1.10" => match spki.parsed() {
+            Ok(PublicKey::RSA(rsa)) => {
+                let modulus: Vec<u8> = rsa.modulus.iter().copied().skip_while(|&b| b == 0).collect();
+                let bits = modulus.first().map_or(0, |first| modulus.len() * 8 - first.leading_zeros() as usize);
+                format!("RSA {}", bits)
+            }
+            _ => "RSA".to_string(),
+        },
+        "1.2.840.10045.2.1" => format!("EC {}", curve()),
+        "1.2.840.10040.4.1" => match spki.parsed() {
+            Ok(key) => format!("DSA {}", key.key_size()),
+            _ => "DSA".to_string(),
+        },
+        "1.3.101.112" => "Ed25519".to_string(),
+        "1.3.101.113" => "Ed448".to_string(),
+        other => other.to_string(),
+    }
+}
+
+fn describe(der: &[u8]) -> Option<Certificate> {
+    let (_, cert) = parse_x509_certificate(der).ok()?;
+    let validity = cert.validity();
+
+    let mut attrs: Attributes = vec![
+        ("subject", cert.subject().to_string()),
+        ("issuer", cert.issuer().to_string()),
+    ];
+    if let Ok(Some(san)) = cert.subject_alternative_name() {
+        let names: Vec<String> = san.value.general_names.iter().map(|name| match name {
+            GeneralName::DNSName(dns) => dns.to_string(),
+            GeneralName::IPAddress(ip) => match ip.len() {
+                4 => std::net::Ipv4Addr::from(<[u8; 4]>::try_from(*ip).unwrap()).to_string(),
+                16 => std::net::Ipv6Addr::from(<[u8; 16]>::try_from(*ip).unwrap()).to_string(),
+                _ => name.to_string(),
+            },
+            other => other.to_string(),
+        }).collect();
+        if !names.is_empty() {
+            attrs.push(("san", names.join(",")));
+        }
+    }
+    attrs.push(("not-before", format_time(validity.not_before.to_datetime())));
+    attrs.push(("not-after", format_time(validity.not_after.to_datetime())));
+    attrs.push(("key", describe_key(&cert)));
+    if validity.not_after.to_datetime() < OffsetDateTime::now_utc() {
+        attrs.push(("expired", "true".to_string()));
+    }
+    if cert.subject().as_raw() == cert.issuer().as_raw() {
+        attrs.push(("self-signed", "true".to_string()));
+    }
+
+    let fingerprint = Sha256::digest(der).iter().map(|b| format!("{:02x}", b)).collect();
+    Some(Certificate { fingerprint, attrs })
+}
+
+// Decode a PEM CERTIFICATE block as collected by pem::PemCollector
+pub fn from_pem(block: &str) -> Option<Certificate> {
+    let body: String = block
+        .lines()
+        .filter(|line| !line.starts_with("-----") && !line.contains(':'))
+        .collect();
+    describe(&STANDARD.decode(body).ok()?)
+}
+
+// Find DER encoded certificates in raw data. A certificate is a SEQUENCE with a two byte
+// length (30 82 xx xx) whose first element, the TBSCertificate, is one as well.
+pub fn find_der_certificates(data: &[u8]) -> Vec<(usize, Certificate)> {
+    let mut found = Vec::new();
+    let mut i = 0;
+
+    while i + 8 <= data.len() {
+        if data[i..i + 2] == [0x30, 0x82] && data[i + 4..i + 6] == [0x30, 0x82] {
+            let end = i + 4 + u16::from_be_bytes([data[i + 2], data[i + 3]]) as usize;
+            if let Some(certificate) = data.get(i..end).and_then(describe) {
+                found.push((i, certificate));
+                i = end;
+                continue;
+            }
+        }
+        i += 1;
+    }
+
+    found
+}
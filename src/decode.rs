@@ -0,0 +1,106 @@
+use std::io::Read;
+use base64::Engine;
+use base64::alphabet;
+use base64::engine::{DecodePaddingMode, GeneralPurpose, GeneralPurposeConfig};
+use flate2::read::{GzDecoder, ZlibDecoder};
+use sha2::{Digest, Sha256};
+use crate::cert::{self, Certificate};
+use crate::StringType;
+
+// Padding is often cut off by the Base64 regex's trailing word boundary
+static BASE64: GeneralPurpose = GeneralPurpose::new(
+    &alphabet::STANDARD,
+    GeneralPurposeConfig::new()
+        .with_decode_padding_mode(DecodePaddingMode::Indifferent)
+        .with_decode_allow_trailing_bits(true),
+);
+
+// Upper bounds on decompressed output and on nested compression layers, so a small blob
+// can't expand into gigabytes or unwrap forever (gzip quines exist)
+const MAX_DECOMPRESSED: u64 = 1 << 20;
+const MAX_LAYERS: usize = 4;
+
+// Binary formats recognised by their leading bytes
+const MAGIC: &[(&[u8], &str)] = &[
+    (b"\x7fELF", "elf"),
+    (b"\x89PNG", "png"),
+    (b"\xff\xd8\xff", "jpeg"),
+    (b"GIF8", "gif"),
+    (b"%PDF", "pdf"),
+    (b"PK\x03\x04", "zip"),
+    (b"BZh", "bzip2"),
+    (b"\xfd7zXZ", "xz"),
+    (b"7z\xbc\xaf", "7z"),
+];
+
+pub enum Payload {
+    Text(String),
+    Certificate(Certificate),
+    // A file in one of the MAGIC formats, identified by the SHA-256 of its content
+    File { format: &'static str, size: usize, sha256: String },
+}
+
+// What an encoded value turned out to hold, and the decodings it took (base64, gzip, ...)
+pub struct Decoded {
+    pub chain: Vec<&'static str>,
+    pub payload: Payload,
+}
+
+fn decompress(data: &[u8]) -> Option<(&'static str, Vec<u8>)> {
+    let mut output = Vec::new();
+    let layer = if data.starts_with(&[0x1f, 0x8b]) {
+        GzDecoder::new(data).take(MAX_DECOMPRESSED).read_to_end(&mut output).ok()?;
+        "gzip"
+    } else if data.len() > 2 && data[0] == 0x78 && u16::from_be_bytes([data[0], data[1]]).is_multiple_of(31) {
+        ZlibDecoder::new(data).take(MAX_DECOMPRESSED).read_to_end(&mut output).ok()?;
+        "zlib"
+    } else {
+        return None;
+    };
+    Some((layer, output))
+}
+
+fn printable(text: &str) -> bool {
+    !text.trim().is_empty() && text.chars().all(|c| !c.is_control() || matches!(c, '\t' | '\r' | '\n'))
+}
+
+// Decode a Base64 or Hex match, unwrapping gzip/zlib layers. Only text, certificates and
+// known binary formats count; anything else was most likely not an encoded value at all.
+pub fn decode(string_type: StringType, value: &str) -> Option<Decoded> {
+    let (encoding, mut data) = match string_type {
+        StringType::HexString => {
+            let digits = value.strip_prefix("0x").unwrap_or(value);
+            if !digits.len().is_multiple_of(2) {
+                return None;
+            }
+            let bytes = (0..digits.len()).step_by(2).map(|i| u8::from_str_radix(&digits[i..i + 2], 16).ok()).collect::<Option<Vec<u8>>>()?;
+            ("hex", bytes)
+        }
+        StringType::Base64String => ("base64", BASE64.decode(value.trim_end_matches('=')).ok()?),
+        _ => return None,
+    };
+
+    let mut chain = vec![encoding];
+    for _ in 0..MAX_LAYERS {
+        let Some((layer, output)) = decompress(&data) else {
+            break;
+        };
+        chain.push(layer);
+        data = output;
+    }
+
+    let certificate = if data.starts_with(&[0x30, 0x82]) { cert::find_der_certificates(&data).into_iter().find(|(offset, _)| *offset == 0) } else { None };
+    let payload = if let Some((_, certificate)) = certificate {
+        Payload::Certificate(certificate)
+    } else if let Some((_, format)) = MAGIC.iter().find(|(magic, _)| data.starts_with(magic)) {
+        let sha256 = Sha256::digest(&data).iter().map(|b| format!("{:02x}", b)).collect();
+        Payload::File { format, size: data.len(), sha256 }
+    } else {
+        let text = String::from_utf8(data).ok()?;
+        if !printable(&text) {
+            return None;
+        }
+        Payload::Text(text)
+    };
+    Some(Decoded { chain, payload })
+}
@@ -0,0 +1,151 @@
+use std::collections::HashSet;
+use regex::Regex;
+use once_cell::sync::Lazy;
+
+// Snapshot of https://publicsuffix.org/list/public_suffix_list.dat (ICANN and private sections)
+static PUBLIC_SUFFIX_LIST: &str = include_str!("public_suffix_list.dat");
+
+static DOMAIN_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?i)\b(?:[a-z0-9](?:[a-z0-9-]{0,61}[a-z0-9])?\.)+[a-z][a-z0-9-]{0,61}[a-z0-9]\b").unwrap());
+
+// Old generic TLDs that are unlikely to show up as the last part of a code identifier
+// ("info" is not one of them: log.info)
+const COMMON_TLDS: &[&str] = &["com", "net", "org", "edu", "gov", "mil", "int", "biz"];
+
+// Country codes that double as file extensions or English words ("libc.so", "main.py", "user.id")
+const AMBIGUOUS_TLDS: &[&str] = &[
+    "am", "an", "as", "at", "be", "by", "cc", "do", "id", "in", "is", "it", "md", "me", "mo",
+    "my", "no", "on", "pl", "pm", "ps", "py", "rs", "sh", "so", "to", "us",
+];
+
+struct PublicSuffixList {
+    rules: HashSet<&'static str>,
+    // "*.ck" is stored as "ck"
+    wildcards: HashSet<&'static str>,
+    // "!www.ck" is stored as "www.ck"
+    exceptions: HashSet<&'static str>,
+}
+
+static PUBLIC_SUFFIXES: Lazy<PublicSuffixList> = Lazy::new(|| {
+    let mut list = PublicSuffixList { rules: HashSet::new(), wildcards: HashSet::new(), exceptions: HashSet::new() };
+    for line in PUBLIC_SUFFIX_LIST.lines() {
+        let rule = line.split_whitespace().next().unwrap_or("");
+        if rule.is_empty() || rule.starts_with("//") {
+            continue;
+        }
+        if let Some(exception) = rule.strip_prefix('!') {
+            list.exceptions.insert(exception);
+        } else if let Some(wildcard) = rule.strip_prefix("*.") {
+            list.wildcards.insert(wildcard);
+        } else {
+            list.rules.insert(rule);
+        }
+    }
+    list
+});
+
+impl PublicSuffixList {
+    // Number of labels in the public suffix of `labels`, or None when no rule matches,
+    // i.e. the TLD isn't a real one (we don't apply the implicit "*" rule)
+    fn suffix_len(&self, labels: &[&str]) -> Option<usize> {
+        for i in 0..labels.len() {
+            let candidate = labels[i..].join(".");
+            if self.exceptions.contains(candidate.as_str()) {
+                return Some(labels.len() - i - 1);
+            }
+            if self.rules.contains(candidate.as_str()) {
+                return Some(labels.len() - i);
+            }
+            if i + 1 < labels.len() && self.wildcards.contains(labels[i + 1..].join(".").as_str()) {
+                return Some(labels.len() - i);
+            }
+        }
+        None
+    }
+}
+
+// `hostname_context` is set when the name follows "://" or '@', where it can only be a host
+fn is_plausible_domain(domain: &str, hostname_context: bool) -> bool {
+    let labels: Vec<&str> = domain.split('.').collect();
+    let Some(suffix_len) = PUBLIC_SUFFIXES.suffix_len(&labels) else {
+        return false;
+    };
+    // Needs a registrable part in front of the public suffix
+    if labels.len() <= suffix_len {
+        return false;
+    }
+
+    let tld = labels[labels.len() - 1];
+    // Reverse-DNS identifiers such as com.example.app
+    if COMMON_TLDS.contains(&labels[0]) {
+        return false;
+    }
+    // Code (obj.name, this.state.map, main.py) collides with real TLDs at any number of labels,
+    // so only the classic gTLDs and country codes that aren't also extensions or words are taken
+    // on their own. Anything else needs something identifiers don't have: a hyphen or digit, a
+    // www. prefix, or a place only hostnames go.
+    if COMMON_TLDS.contains(&tld) || (tld.len() == 2 && !AMBIGUOUS_TLDS.contains(&tld)) {
+        return true;
+    }
+    hostname_context || labels[0] == "www" || labels.iter().any(|label| label.contains(|c: char| c == '-' || c.is_ascii_digit()))
+}
+
+// Find hostnames whose suffix is on the public suffix list, returning each (lowercased)
+// domain and the offset where it starts
+pub fn find_domains(line: &str) -> Vec<(usize, String)> {
+    let mut found = Vec::new();
+
+    for mat in DOMAIN_REGEX.find_iter(line) {
+        let candidate = mat.as_str();
+        // Mixed case is a qualified identifier (System.Console), not a hostname
+        if candidate.contains(|c: char| c.is_ascii_lowercase()) && candidate.contains(|c: char| c.is_ascii_uppercase()) {
+            continue;
+        }
+
+        let before = &line[..mat.start()];
+        let after = &line[mat.end()..];
+        // Part of a longer dotted name the regex couldn't take whole (libssl.so.1.1, 1.2.foo),
+        // an email local part, or a method call
+        if before.ends_with('.') || after.starts_with('@') || after.starts_with('(') {
+            continue;
+        }
+        if after.starts_with('.') && after[1..].starts_with(|c: char| c.is_ascii_alphanumeric()) {
+            continue;
+        }
+
+        let domain = candidate.to_ascii_lowercase();
+        if is_plausible_domain(&domain, before.ends_with("://") || before.ends_with('@')) {
+            found.push((mat.start(), domain));
+        }
+    }
+
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn domains(line: &str) -> Vec<String> {
+        find_domains(line).into_iter().map(|(_, domain)| domain).collect()
+    }
+
+    #[test]
+    fn hostnames() {
+        for host in ["example.com", "update.evil-cdn.net", "news.bbc.co.uk", "www.example.me", "api-v2.example.app", "cdn1.example.info"] {
+            assert_eq!(domains(host), vec![host], "{}", host);
+        }
+    }
+
+    #[test]
+    fn hostname_context() {
+        assert_eq!(domains("https://maps.example.app/x"), vec!["maps.example.app"]);
+        assert_eq!(domains("ops@mail.example.info"), vec!["mail.example.info"]);
+    }
+
+    #[test]
+    fn code_identifiers() {
+        for identifier in ["this.state.map", "window.location.host", "req.body.data", "process.env.name", "self.config.app", "log.info", "main.py", "user.id", "com.example.app"] {
+            assert!(domains(identifier).is_empty(), "{}", identifier);
+        }
+    }
+}
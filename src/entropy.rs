@@ -0,0 +1,140 @@
+use std::collections::HashMap;
+use regex::Regex;
+use once_cell::sync::{Lazy, OnceCell};
+
+// Runs of token characters long enough to hold a key: hex, alphanumeric, base64 and base64url
+static TOKEN_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"[A-Za-z0-9+/_-]{20,}={0,2}").unwrap());
+
+// Default minimum Shannon entropy (bits per character) per charset. Random 20 character strings
+// sit around 3.4 (hex) and 4.0 (alphanumeric/base64); the defaults leave room for the unlucky ones.
+const CHARSET_THRESHOLDS: &[(&str, f64)] = &[
+    ("hex", 3.0),
+    ("alphanumeric", 3.7),
+    ("base64", 3.8),
+    ("base64url", 3.8),
+];
+
+// A single threshold from --min-entropy, replacing the per-charset defaults
+static MIN_ENTROPY: OnceCell<Option<f64>> = OnceCell::new();
+
+pub fn configure(min_entropy: Option<f64>) {
+    let _ = MIN_ENTROPY.set(min_entropy);
+}
+
+fn threshold(charset: &str) -> f64 {
+    if let Some(&Some(min_entropy)) = MIN_ENTROPY.get() {
+        return min_entropy;
+    }
+    CHARSET_THRESHOLDS.iter().find(|(c, _)| *c == charset).map_or(f64::MAX, |(_, t)| *t)
+}
+
+fn shannon_entropy(value: &str) -> f64 {
+    let mut counts: HashMap<char, usize> = HashMap::new();
+    for c in value.chars() {
+        *counts.entry(c).or_default() += 1;
+    }
+    let len = value.chars().count() as f64;
+    counts.values().map(|&n| {
+        let p = n as f64 / len;
+        -p * p.log2()
+    }).sum()
+}
+
+// Which alphabet a candidate is written in, or None if it mixes the two base64 variants
+// (paths such as usr/lib/x86_64-linux-gnu) or has padding in the middle
+fn charset(value: &str) -> Option<&'static str> {
+    let body = value.trim_end_matches('=');
+    if body.contains('=') {
+        return None;
+    }
+    let padded = body.len() != value.len();
+    match (body.contains(['+', '/']), body.contains(['-', '_'])) {
+        (true, true) => None,
+        (false, true) => Some("base64url"),
+        (true, false) => Some("base64"),
+        _ if padded => Some("base64"),
+        _ if body.chars().all(|c| c.is_ascii_hexdigit()) => Some("hex"),
+        _ => Some("alphanumeric"),
+    }
+}
+
+// Average length of the words in a name, splitting at separators and wherever the character
+// class changes (a capital starts a word: Py/Unicode/Decode/UTF/16). Names average well above
+// 3 characters; the class of random characters changes every couple of positions.
+fn average_word_len(value: &str) -> f64 {
+    #[derive(PartialEq, Clone, Copy)]
+    enum Class { Upper, Lower, Digit }
+
+    let mut words = 0;
+    let mut letters = 0;
+    let mut previous: Option<Class> = None;
+    let mut run = 0;
+    for c in value.chars() {
+        let class = match c {
+            'A'..='Z' => Class::Upper,
+            'a'..='z' => Class::Lower,
+            '0'..='9' => Class::Digit,
+            _ => {
+                previous = None;
+                continue;
+            }
+        };
+        let continues = match previous {
+            Some(Class::Upper) if class == Class::Lower => run == 1,
+            Some(p) => p == class,
+            None => false,
+        };
+        if continues {
+            run += 1;
+        } else {
+            words += 1;
+            run = 1;
+        }
+        letters += 1;
+        previous = Some(class);
+    }
+    if words == 0 { 0.0 } else { letters as f64 / words as f64 }
+}
+
+// Shapes that score well but aren't random: symbol names (PyUnicode_AsUTF8String,
+// _LARGEFILE64_SOURCE, getHttpClientConfiguration2), C++ mangled names and alphabets (ABCDEFGHIJ...)
+fn looks_structured(value: &str) -> bool {
+    if !value.contains(|c: char| c.is_ascii_digit()) || !value.contains(|c: char| c.is_ascii_alphabetic()) {
+        return true;
+    }
+    if value.starts_with("_Z") || average_word_len(value) >= 3.0 {
+        return true;
+    }
+    let bytes = value.as_bytes();
+    let sequential = bytes.windows(2).filter(|w| w[1] == w[0].wrapping_add(1)).count();
+    sequential * 3 > bytes.len()
+}
+
+pub struct HighEntropyMatch {
+    pub offset: usize,
+    pub value: String,
+    pub charset: &'static str,
+    pub entropy: f64,
+}
+
+// Find random looking tokens in a line: candidates of 20+ token characters whose entropy
+// reaches the threshold for their charset
+pub fn find_high_entropy(line: &str) -> Vec<HighEntropyMatch> {
+    let mut found = Vec::new();
+
+    for mat in TOKEN_REGEX.find_iter(line) {
+        let value = mat.as_str();
+        let Some(charset) = charset(value) else {
+            continue;
+        };
+        if looks_structured(value) {
+            continue;
+        }
+        let entropy = shannon_entropy(value.trim_end_matches('='));
+        if entropy >= threshold(charset) {
+            found.push(HighEntropyMatch { offset: mat.start(), value: value.to_string(), charset, entropy });
+        }
+    }
+
+    found
+}
@@ -0,0 +1,190 @@
+use std::collections::HashSet;
+use clap::ValueEnum;
+
+// Encodings we know how to pull printable runs out of
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, ValueEnum)]
+#[clap(rename_all = "kebab-case")]
+pub enum Encoding {
+    Ascii,
+    Utf16le,
+    Utf16be,
+    All,
+}
+
+impl Encoding {
+    fn expand(encodings: &[Encoding]) -> Vec<Encoding> {
+        if encodings.is_empty() || encodings.contains(&Encoding::All) {
+            vec![Encoding::Ascii, Encoding::Utf16le, Encoding::Utf16be]
+        } else {
+            encodings.to_vec()
+        }
+    }
+}
+
+// A printable run found in a binary, with the byte offset where it starts
+#[derive(Debug, Clone)]
+pub struct StringRun {
+    pub offset: usize,
+    pub text: String,
+    pub wide: bool,
+}
+
+impl StringRun {
+    pub fn byte_len(&self) -> usize {
+        if self.wide { self.text.len() * 2 } else { self.text.len() }
+    }
+}
+
+// Same notion of "printable" as GNU strings: graphic ASCII, space and tab
+fn is_printable(b: u8) -> bool {
+    b == b'\t' || (0x20..=0x7e).contains(&b)
+}
+
+fn extract_ascii(data: &[u8], min_len: usize, runs: &mut Vec<StringRun>) {
+    let mut start = 0;
+    let mut current = String::new();
+
+    for (i, &b) in data.iter().enumerate() {
+        if is_printable(b) {
+            if current.is_empty() {
+                start = i;
+            }
+            current.push(b as char);
+        } else {
+            if current.len() >= min_len {
+                runs.push(StringRun { offset: start, text: std::mem::take(&mut current), wide: false });
+            }
+            current.clear();
+        }
+    }
+    if current.len() >= min_len {
+        runs.push(StringRun { offset: start, text: current, wide: false });
+    }
+}
+
+// UTF-16 runs are only recognised for code units in the printable ASCII range,
+// anything wider is indistinguishable from random data in practice
+fn extract_utf16(data: &[u8], min_len: usize, big_endian: bool, runs: &mut Vec<StringRun>) {
+    for alignment in 0..2 {
+        let mut start = 0;
+        let mut current = String::new();
+        let mut i = alignment;
+
+        while i + 1 < data.len() {
+            let (lo, hi) = if big_endian { (data[i + 1], data[i]) } else { (data[i], data[i + 1]) };
+            if hi == 0 && is_printable(lo) {
+                if current.is_empty() {
+                    start = i;
+                }
+                current.push(lo as char);
+            } else {
+                if current.len() >= min_len {
+                    runs.push(StringRun { offset: start, text: std::mem::take(&mut current), wide: true });
+                }
+                current.clear();
+            }
+            i += 2;
+        }
+        if current.len() >= min_len {
+            runs.push(StringRun { offset: start, text: current, wide: true });
+        }
+    }
+}
+
+// Extract printable runs of at least `min_len` characters, ordered by offset
+pub fn extract_strings(data: &[u8], min_len: usize, encodings: &[Encoding]) -> Vec<StringRun> {
+    let min_len = min_len.max(1);
+    let mut runs = Vec::new();
+
+    for encoding in Encoding::expand(encodings) {
+        match encoding {
+            Encoding::Ascii => extract_ascii(data, min_len, &mut runs),
+            Encoding::Utf16le => extract_utf16(data, min_len, false, &mut runs),
+            Encoding::Utf16be => extract_utf16(data, min_len, true, &mut runs),
+            Encoding::All => unreachable!("expanded above"),
+        }
+    }
+
+    // A UTF-16LE run read one byte off is also a valid UTF-16BE run (and vice versa),
+    // so drop wide runs that sit entirely inside a longer one
+    runs.sort_by(|a, b| a.offset.cmp(&b.offset).then(b.byte_len().cmp(&a.byte_len())));
+    let mut wide_end = 0;
+    runs.retain(|run| {
+        if !run.wide {
+            return true;
+        }
+        let end = run.offset + run.byte_len();
+        if end <= wide_end {
+            return false;
+        }
+        wide_end = end;
+        true
+    });
+
+    // A wide string next to a NUL byte reads the same one byte off in the other byte order
+    // (e.g. "\0h\0i\0" is both "hi" in LE at 1 and "hi" in BE at 0). UTF-16 strings are 2-byte
+    // aligned in practice, so of two such runs keep the one at the even offset.
+    let aligned: HashSet<(usize, String)> = runs
+        .iter()
+        .filter(|run| run.wide && run.offset % 2 == 0)
+        .map(|run| (run.offset, run.text.clone()))
+        .collect();
+    runs.retain(|run| {
+        !(run.wide
+            && run.offset % 2 == 1
+            && (aligned.contains(&(run.offset - 1, run.text.clone())) || aligned.contains(&(run.offset + 1, run.text.clone()))))
+    });
+    runs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utf16le(text: &str) -> Vec<u8> {
+        text.bytes().flat_map(|b| [b, 0]).collect()
+    }
+
+    fn utf16be(text: &str) -> Vec<u8> {
+        text.bytes().flat_map(|b| [0, b]).collect()
+    }
+
+    fn found(data: &[u8]) -> Vec<(usize, String, bool)> {
+        extract_strings(data, 4, &[]).into_iter().map(|run| (run.offset, run.text, run.wide)).collect()
+    }
+
+    #[test]
+    fn ascii() {
+        assert_eq!(found(b"\x01\x02hello\0world\xff"), vec![(2, "hello".to_string(), false), (8, "world".to_string(), false)]);
+    }
+
+    #[test]
+    fn utf16_le() {
+        let mut data = vec![0xff, 0xfe];
+        data.extend(utf16le("http://wide.com/"));
+        assert_eq!(found(&data), vec![(2, "http://wide.com/".to_string(), true)]);
+    }
+
+    #[test]
+    fn utf16_be() {
+        let mut data = vec![0xfe, 0xff];
+        data.extend(utf16be("http://wide.com/"));
+        assert_eq!(found(&data), vec![(2, "http://wide.com/".to_string(), true)]);
+    }
+
+    #[test]
+    fn utf16_le_after_nul() {
+        let mut data = utf16le("AB");
+        data.extend([0, 0]);
+        data.extend(utf16le("http://wide.com/"));
+        data.extend([0, 0]);
+        assert_eq!(found(&data), vec![(6, "http://wide.com/".to_string(), true)]);
+    }
+
+    #[test]
+    fn utf16_be_nul_terminated() {
+        let mut data = utf16be("http://wide.com/");
+        data.extend([0, 0, 0xff, 0xff]);
+        assert_eq!(found(&data), vec![(0, "http://wide.com/".to_string(), true)]);
+    }
+}
@@ -0,0 +1,78 @@
+use std::net::IpAddr;
+use std::path::PathBuf;
+use maxminddb::{geoip2, MaxMindDBError, Reader};
+use crate::{network, Attributes, Collections, StringType};
+
+// Local MaxMind databases (GeoLite2/GeoIP2 Country, City or ASN). Every database is asked
+// for every field, so a country and an ASN database can be combined freely.
+pub struct GeoIp {
+    readers: Vec<Reader<Vec<u8>>>,
+}
+
+impl GeoIp {
+    pub fn open(paths: &[PathBuf]) -> Result<Self, String> {
+        let readers = paths
+            .iter()
+            .map(|path| Reader::open_readfile(path).map_err(|e| format!("{}: {}", path.display(), e)))
+            .collect::<Result<_, _>>()?;
+        Ok(GeoIp { readers })
+    }
+
+    fn lookup(&self, ip: IpAddr) -> Attributes {
+        let mut country = None;
+        let mut asn = None;
+        let mut organization = None;
+
+        for reader in &self.readers {
+            match reader.lookup::<geoip2::Country>(ip) {
+                Ok(record) => {
+                    let iso_code = record.country.and_then(|c| c.iso_code)
+                        .or(record.registered_country.and_then(|c| c.iso_code));
+                    country = country.or(iso_code.map(str::to_string));
+                }
+                Err(MaxMindDBError::AddressNotFoundError(_)) => {}
+                Err(e) => eprintln!("Error: geoip lookup for {}: {}", ip, e),
+            }
+            if let Ok(record) = reader.lookup::<geoip2::Asn>(ip) {
+                asn = asn.or(record.autonomous_system_number);
+                organization = organization.or(record.autonomous_system_organization.map(str::to_string));
+            }
+        }
+
+        let mut attrs = Vec::new();
+        if let Some(country) = country {
+            attrs.push(("country", country));
+        }
+        if let Some(asn) = asn {
+            attrs.push(("asn", format!("AS{}", asn)));
+        }
+        if let Some(organization) = organization {
+            attrs.push(("as-org", organization));
+        }
+        attrs
+    }
+
+    // Add country/ASN attributes to every public IP address that was collected
+    pub fn enrich(&self, collections: &mut Collections) {
+        for string_type in [StringType::IPv4String, StringType::IPv6String] {
+            let Some(strings) = collections.get_mut(&string_type) else {
+                continue;
+            };
+            for (value, finding) in strings.iter_mut() {
+                if finding.attr("scope") != Some(network::PUBLIC_SCOPE) {
+                    continue;
+                }
+                let address = value.split('%').next().unwrap_or(value);
+                let Ok(ip) = address.parse::<IpAddr>() else {
+                    continue;
+                };
+                // IPv4-mapped addresses are stored under their IPv4 form in the databases
+                let ip = match ip {
+                    IpAddr::V6(v6) => v6.to_ipv4_mapped().map_or(ip, IpAddr::V4),
+                    v4 => v4,
+                };
+                finding.attrs.extend(self.lookup(ip));
+            }
+        }
+    }
+}
@@ -0,0 +1,237 @@
+use std::collections::HashMap;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use crate::{Match, StringType};
+
+// Digests written with their algorithm: OCI/Docker `sha256:<hex>` and Subresource Integrity
+// `sha384-<base64>`
+static OCI_DIGEST_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"\b(?P<alg>sha256|sha384|sha512):(?P<digest>[a-f0-9]{64,128})\b").unwrap());
+static SRI_DIGEST_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"\b(?P<alg>sha256|sha384|sha512)-(?P<digest>[A-Za-z0-9+/]{43,86}={0,2})").unwrap());
+
+// Password hash formats: (scheme, name of the work factor attribute, regex). A `variant` group
+// overrides the scheme name, `cost` captures the work factor.
+static PASSWORD_HASHES: Lazy<Vec<(&str, &str, Regex)>> = Lazy::new(|| {
+    [
+        ("bcrypt", "cost", r"\$2[abxy]?\$(?P<cost>\d{2})\$[./A-Za-z0-9]{53}"),
+        ("md5-crypt", "rounds", r"\$1\$[./A-Za-z0-9]{1,8}\$[./A-Za-z0-9]{22}"),
+        ("sha256-crypt", "rounds", r"\$5\$(?:rounds=(?P<cost>\d+)\$)?[./A-Za-z0-9]{1,16}\$[./A-Za-z0-9]{43}"),
+        ("sha512-crypt", "rounds", r"\$6\$(?:rounds=(?P<cost>\d+)\$)?[./A-Za-z0-9]{1,16}\$[./A-Za-z0-9]{86}"),
+        ("argon2", "params", r"\$(?P<variant>argon2(?:id|i|d))\$(?:v=\d+\$)?(?P<cost>m=\d+,t=\d+,p=\d+)\$[A-Za-z0-9+/]+\$[A-Za-z0-9+/]{16,}"),
+        ("pbkdf2", "iterations", r"\$?(?P<variant>pbkdf2(?:[_-]sha(?:1|256|512))?)\$(?P<cost>\d+)\$[A-Za-z0-9./+=]+\$[A-Za-z0-9./+=]{16,}"),
+    ]
+    .into_iter()
+    .map(|(scheme, cost, pattern)| (scheme, cost, Regex::new(pattern).unwrap()))
+    .collect()
+});
+
+// Types that all claim the same run of hex digits
+const HEX_FAMILY: &[StringType] = &[
+    StringType::MD5HashString,
+    StringType::SHA1HashString,
+    StringType::SHA256HashString,
+    StringType::SHA512HashString,
+    StringType::SHA224HashString,
+    StringType::SHA384HashString,
+    StringType::BLAKE2HashString,
+    StringType::BLAKE3HashString,
+    StringType::NTLMHashString,
+    StringType::GitHashString,
+    StringType::HexString,
+    StringType::Base64String,
+];
+
+// Words just before a value that point at one reading of it (commit 1a2b..., sha256:..., md5sum)
+const CONTEXT_WORDS: &[(StringType, &[&str])] = &[
+    (StringType::GitHashString, &["commit", "parent", "tree", "git", "revision", "rev"]),
+    (StringType::MD5HashString, &["md5", "md5sum"]),
+    (StringType::SHA1HashString, &["sha1", "sha-1", "sha1sum"]),
+    (StringType::SHA256HashString, &["sha256", "sha-256", "sha256sum"]),
+    (StringType::SHA512HashString, &["sha512", "sha-512", "sha512sum"]),
+    (StringType::SHA224HashString, &["sha224", "sha-224", "sha224sum"]),
+    (StringType::SHA384HashString, &["sha384", "sha-384", "sha384sum"]),
+    (StringType::BLAKE2HashString, &["blake2", "b2sum"]),
+    (StringType::BLAKE3HashString, &["blake3", "b3sum"]),
+    (StringType::NTLMHashString, &["ntlm", "nthash"]),
+    (StringType::HexString, &["0x", "hex"]),
+    (StringType::Base64String, &["base64"]),
+];
+
+const CONTEXT_WINDOW: usize = 32;
+
+// How likely a value of this length is to be this type, before looking at context
+fn base_weight(string_type: StringType, len: usize) -> f64 {
+    match string_type {
+        StringType::MD5HashString | StringType::SHA1HashString => 3.0,
+        StringType::SHA256HashString | StringType::SHA512HashString => 4.0,
+        StringType::SHA224HashString | StringType::SHA384HashString => 4.0,
+        StringType::GitHashString if len == 40 => 2.0,
+        // Same lengths as SHA-256/512 and MD5; only ahead of those when the context names them
+        StringType::BLAKE2HashString | StringType::BLAKE3HashString | StringType::NTLMHashString => 1.25,
+        StringType::Base64String => 0.2,
+        _ => 1.0,
+    }
+}
+
+// Whole words of the (lowercased) context window, leaving out one cut in half by the window's
+// start, so that "digits" doesn't count as "git" or "previous" as "rev"
+fn context_words<'a>(line: &str, start: usize, context: &'a str) -> Vec<&'a str> {
+    let mut words: Vec<&str> = context.split(|c: char| !(c.is_ascii_alphanumeric() || c == '-')).filter(|w| !w.is_empty()).collect();
+    if line[..start].ends_with(|c: char| c.is_ascii_alphanumeric() || c == '-') && context.starts_with(|c: char| c.is_ascii_alphanumeric() || c == '-') {
+        words.remove(0);
+    }
+    words
+}
+
+fn weight(string_type: StringType, len: usize, context: &[&str]) -> f64 {
+    let mentioned = CONTEXT_WORDS
+        .iter()
+        .any(|(t, words)| *t == string_type && words.iter().any(|w| context.contains(w)));
+    base_weight(string_type, len) * if mentioned { 4.0 } else { 1.0 }
+}
+
+pub fn find_digests(line: &str) -> Vec<Match> {
+    let mut found = Vec::new();
+    for caps in OCI_DIGEST_REGEX.captures_iter(line) {
+        let (alg, digest) = (&caps["alg"], &caps["digest"]);
+        if digest_len(alg) * 2 == digest.len() {
+            let mat = caps.get(0).unwrap();
+            found.push(Match::new(StringType::DigestString, mat.as_str(), mat.start()).with_attr("algorithm", alg).with_attr("format", "oci"));
+        }
+    }
+    for caps in SRI_DIGEST_REGEX.captures_iter(line) {
+        let alg = &caps["alg"];
+        let Ok(digest) = STANDARD.decode(&caps["digest"]) else {
+            continue;
+        };
+        if digest.len() == digest_len(alg) {
+            let mat = caps.get(0).unwrap();
+            let hex: String = digest.iter().map(|b| format!("{:02x}", b)).collect();
+            found.push(
+                Match::new(StringType::DigestString, mat.as_str(), mat.start())
+                    .with_attr("algorithm", alg)
+                    .with_attr("format", "sri")
+                    .with_attr("hex", hex),
+            );
+        }
+    }
+    found
+}
+
+fn digest_len(alg: &str) -> usize {
+    match alg {
+        "sha256" => 32,
+        "sha384" => 48,
+        _ => 64,
+    }
+}
+
+pub fn find_password_hashes(line: &str) -> Vec<Match> {
+    let mut found = Vec::new();
+    for (scheme, cost_key, regex) in PASSWORD_HASHES.iter() {
+        for caps in regex.captures_iter(line) {
+            let mat = caps.get(0).unwrap();
+            let scheme = caps.name("variant").map_or(scheme.to_string(), |v| v.as_str().replace('_', "-"));
+            let mut m = Match::new(StringType::PasswordHashString, mat.as_str(), mat.start()).with_attr("scheme", scheme);
+            if let Some(cost) = caps.name("cost") {
+                m = m.with_attr(cost_key, cost.as_str());
+            }
+            found.push(m);
+        }
+    }
+    found
+}
+
+// Collapse matches of the hex family that cover exactly the same span into one: the most
+// likely enabled type, with the other readings as `alternates` and a `confidence` score.
+// Hex, base64 and path-like pieces of a prefixed digest or password hash belong to that and
+// are dropped.
+pub fn resolve_overlaps(matches: Vec<Match>, line: &str, enabled: impl Fn(StringType) -> bool) -> Vec<Match> {
+    let claimed: Vec<(usize, usize)> = matches
+        .iter()
+        .filter(|m| matches!(m.string_type, StringType::DigestString | StringType::PasswordHashString))
+        .map(|m| (m.offset, m.offset + m.value.len()))
+        .collect();
+    let mut spans: HashMap<(usize, usize), Vec<Match>> = HashMap::new();
+    let mut resolved = Vec::new();
+    for m in matches {
+        if (HEX_FAMILY.contains(&m.string_type) || m.string_type == StringType::PathString)
+            && claimed.iter().any(|&(start, end)| m.overlaps(start, end))
+        {
+            continue;
+        }
+        if HEX_FAMILY.contains(&m.string_type) {
+            spans.entry((m.offset, m.value.len())).or_default().push(m);
+        } else {
+            resolved.push(m);
+        }
+    }
+
+    for ((offset, len), mut candidates) in spans {
+        if candidates.len() == 1 {
+            resolved.extend(candidates);
+            continue;
+        }
+
+        let start = line.floor_char_boundary(offset.saturating_sub(CONTEXT_WINDOW));
+        let context = line[start..offset].to_ascii_lowercase();
+        let words = context_words(line, start, &context);
+        let mut weighted: Vec<(f64, Match)> = candidates
+            .drain(..)
+            .map(|m| (weight(m.string_type, len, &words), m))
+            .collect();
+        weighted.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+        let total: f64 = weighted.iter().map(|(w, _)| w).sum();
+        let Some(primary) = weighted.iter().position(|(_, m)| enabled(m.string_type)) else {
+            continue;
+        };
+        let alternates: Vec<&str> = weighted
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| *i != primary)
+            .map(|(_, (_, m))| m.string_type.as_str())
+            .collect();
+        let alternates = alternates.join(",");
+        let (primary_weight, m) = weighted.swap_remove(primary);
+
+        resolved.push(
+            m.with_attr("alternates", alternates)
+                .with_attr("confidence", format!("{:.2}", primary_weight / total)),
+        );
+    }
+
+    resolved
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::extract_all_matches;
+
+    const SHA1: &str = "da39a3ee5e6b4b0d3255bfef95601890afd80709";
+
+    fn primary(line: &str) -> StringType {
+        let matches = resolve_overlaps(extract_all_matches(line), line, |_| true);
+        matches.into_iter().find(|m| m.value == SHA1).unwrap().string_type
+    }
+
+    #[test]
+    fn context_word() {
+        assert_eq!(primary(&format!("commit {}", SHA1)), StringType::GitHashString);
+        assert_eq!(primary(&format!("git rev: {}", SHA1)), StringType::GitHashString);
+    }
+
+    #[test]
+    fn context_word_inside_other_word() {
+        assert_eq!(primary(&format!("checksum digits: {}", SHA1)), StringType::SHA1HashString);
+        assert_eq!(primary(&format!("previous street: {}", SHA1)), StringType::SHA1HashString);
+    }
+
+    #[test]
+    fn context_word_cut_by_window() {
+        // The window starts at the "rev" of "prev"
+        assert_eq!(primary(&format!("prev{:29}{}", "", SHA1)), StringType::SHA1HashString);
+    }
+}
@@ -0,0 +1,61 @@
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use serde_json::{Map, Value};
+use time::OffsetDateTime;
+use crate::{cert, Attributes};
+
+fn decode_part(part: &str) -> Option<Map<String, Value>> {
+    let bytes = URL_SAFE_NO_PAD.decode(part.trim_end_matches('=')).ok()?;
+    match serde_json::from_slice(&bytes).ok()? {
+        Value::Object(object) => Some(object),
+        _ => None,
+    }
+}
+
+fn text(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Array(items) => items.iter().map(text).collect::<Vec<_>>().join(","),
+        other => other.to_string(),
+    }
+}
+
+// Decode a JWT's header and claims: alg/typ/kid, iss/sub/aud, and exp/iat/nbf as dates,
+// flagging unsigned (alg none) and expired tokens. None if it isn't a JWT after all.
+pub fn decode(token: &str) -> Option<Attributes> {
+    let mut parts = token.split('.');
+    let header = decode_part(parts.next()?)?;
+    let claims = decode_part(parts.next()?)?;
+    let alg = header.get("alg")?.as_str()?;
+
+    let mut attrs: Attributes = vec![("alg", alg.to_string())];
+    for key in ["typ", "kid"] {
+        if let Some(value) = header.get(key) {
+            attrs.push((key, text(value)));
+        }
+    }
+    for key in ["iss", "sub", "aud"] {
+        if let Some(value) = claims.get(key) {
+            attrs.push((key, text(value)));
+        }
+    }
+
+    let mut expired = false;
+    for key in ["exp", "iat", "nbf"] {
+        let Some(time) = claims.get(key).and_then(Value::as_i64).and_then(|t| OffsetDateTime::from_unix_timestamp(t).ok()) else {
+            continue;
+        };
+        if key == "exp" {
+            expired = time < OffsetDateTime::now_utc();
+        }
+        attrs.push((key, cert::format_time(time)));
+    }
+
+    if alg.eq_ignore_ascii_case("none") {
+        attrs.push(("alg-none", "true".to_string()));
+    }
+    if expired {
+        attrs.push(("expired", "true".to_string()));
+    }
+    Some(attrs)
+}
@@ -1,10 +1,34 @@
+mod cert;
+mod connstr;
+mod decode;
+mod domain;
+mod entropy;
+mod extract;
+mod geoip;
+mod hashes;
+mod jwt;
+mod network;
+mod output;
+mod pem;
+mod redact;
+mod secrets;
+mod sshkey;
+mod urls;
+mod walk;
+
 use std::io::{self, BufRead, BufReader};
 use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::PathBuf;
+use std::rc::Rc;
 use regex::Regex;
 use once_cell::sync::Lazy;
 use clap::{Parser, ValueEnum};
+use extract::Encoding;
+use output::OutputFormat;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[allow(clippy::enum_variant_names)]
 enum StringType {
     JunkString,
     RegularString,
@@ -13,8 +37,10 @@ enum StringType {
     PathString,
     FormatMessageString,
     SecretString,
+    HighEntropyString,
     URLString,
     EmailString,
+    DomainString,
     UUIDString,
     MACAddressString,
     Base64String,
@@ -34,10 +60,21 @@ enum StringType {
     RustPanicString,
     SQLQueryString,
     SSHKeyString,
+    PemBlockString,
+    CertificateString,
+    EncodedFileString,
+    ConnectionString,
     MD5HashString,
     SHA1HashString,
     SHA256HashString,
     SHA512HashString,
+    SHA224HashString,
+    SHA384HashString,
+    BLAKE2HashString,
+    BLAKE3HashString,
+    NTLMHashString,
+    DigestString,
+    PasswordHashString,
 }
 
 impl StringType {
@@ -50,8 +87,10 @@ impl StringType {
             StringType::PathString => "Path",
             StringType::FormatMessageString => "FormatMessage",
             StringType::SecretString => "Secret",
+            StringType::HighEntropyString => "HighEntropy",
             StringType::URLString => "URL",
             StringType::EmailString => "Email",
+            StringType::DomainString => "Domain",
             StringType::UUIDString => "UUID",
             StringType::MACAddressString => "MACAddress",
             StringType::Base64String => "Base64",
@@ -71,10 +110,21 @@ impl StringType {
             StringType::RustPanicString => "RustPanic",
             StringType::SQLQueryString => "SQLQuery",
             StringType::SSHKeyString => "SSHKey",
+            StringType::PemBlockString => "PemBlock",
+            StringType::CertificateString => "Certificate",
+            StringType::EncodedFileString => "EncodedFile",
+            StringType::ConnectionString => "ConnectionString",
             StringType::MD5HashString => "MD5Hash",
             StringType::SHA1HashString => "SHA1Hash",
             StringType::SHA256HashString => "SHA256Hash",
             StringType::SHA512HashString => "SHA512Hash",
+            StringType::SHA224HashString => "SHA224Hash",
+            StringType::SHA384HashString => "SHA384Hash",
+            StringType::BLAKE2HashString => "BLAKE2Hash",
+            StringType::BLAKE3HashString => "BLAKE3Hash",
+            StringType::NTLMHashString => "NTLMHash",
+            StringType::DigestString => "Digest",
+            StringType::PasswordHashString => "PasswordHash",
         }
     }
 
@@ -119,6 +169,7 @@ impl StringType {
             StringType::IPv6String,
             StringType::URLString,
             StringType::EmailString,
+            StringType::DomainString,
             StringType::MACAddressString,
         ]
     }
@@ -131,22 +182,43 @@ impl StringType {
         ]
     }
 
+    fn hash_types() -> Vec<StringType> {
+        vec![
+            StringType::MD5HashString,
+            StringType::SHA1HashString,
+            StringType::SHA224HashString,
+            StringType::SHA256HashString,
+            StringType::SHA384HashString,
+            StringType::SHA512HashString,
+            StringType::BLAKE2HashString,
+            StringType::BLAKE3HashString,
+            StringType::DigestString,
+        ]
+    }
+
+    // Hashes of passwords, i.e. stored credentials that can be cracked offline
+    fn password_hash_types() -> Vec<StringType> {
+        vec![
+            StringType::PasswordHashString,
+            StringType::NTLMHashString,
+        ]
+    }
+
     fn data_format_types() -> Vec<StringType> {
         vec![
             StringType::JSONString,
             StringType::XMLString,
             StringType::Base64String,
             StringType::HexString,
+            StringType::EncodedFileString,
         ]
     }
 }
 static IPV4_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"\b(?:[0-9]{1,3}\.){3}[0-9]{1,3}\b").unwrap());
-static IPV6_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?i)\b(?:[0-9a-f]{0,4}:){2,7}[0-9a-f]{0,4}\b").unwrap());
 static PATH_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?:[a-zA-Z]:[/\\]|/)(?:[a-zA-Z0-9_.\-]+[/\\])+[a-zA-Z0-9_.\-]+|(?:[a-zA-Z]:[/\\]|/)[a-zA-Z0-9_.\-]+\.[a-zA-Z0-9]+").unwrap());
 static FORMAT_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"%[sdfx]|\{\}|\{[0-9]+\}").unwrap());
-// Match JWT tokens and common API key patterns, but not random alphanumeric strings or mangled names
-static SECRET_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?:ey[A-Za-z0-9_-]{20,}\.[A-Za-z0-9_-]{20,}\.[A-Za-z0-9_-]{20,})|(?:AKIA[0-9A-Z]{16})|(?:(?:sk|pk|api|token)_[A-Za-z0-9]{32,})").unwrap());
-static URL_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?i)\b(?:https?|ftp)://[^\s]+").unwrap());
+// Any scheme (git+ssh, mongodb+srv, s3, myapp, ...), sorted into categories by urls::scheme_category
+static URL_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?i)\b[a-z][a-z0-9+.\-]*://[^\s]+").unwrap());
 static EMAIL_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"\b[A-Za-z0-9._%+-]+@[A-Za-z0-9.-]+\.[A-Z|a-z]{2,}\b").unwrap());
 static UUID_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"\b[0-9a-fA-F]{8}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{12}\b").unwrap());
 static MAC_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"\b(?:[0-9a-fA-F]{2}[:-]){5}[0-9a-fA-F]{2}\b").unwrap());
@@ -160,15 +232,96 @@ static CPP_MANGLED_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"_Z[NKL][0-9a-z
 // Match actual SQL queries with more context - require SELECT/INSERT/UPDATE/DELETE followed by realistic SQL structure
 static SQL_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?i)\b(?:SELECT\s+(?:\*|\w+).*\s+FROM\s+\w+|INSERT\s+INTO\s+\w+.*VALUES|UPDATE\s+\w+\s+SET|DELETE\s+FROM\s+\w+|CREATE\s+TABLE\s+\w+|DROP\s+TABLE\s+\w+)").unwrap());
 
-// SSH keys - match public keys (ssh-rsa, ssh-ed25519, etc.) and private key headers
-static SSH_KEY_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?:ssh-(?:rsa|dss|ed25519|ecdsa)\s+[A-Za-z0-9+/=]{50,}|-----BEGIN\s+(?:RSA|DSA|EC|OPENSSH)\s+(?:PRIVATE|PUBLIC)\s+KEY-----)").unwrap());
+// SSH keys - match public keys (ssh-rsa, ssh-ed25519, ecdsa-sha2-nistp*, sk-* security keys) with
+// the comment that follows them, and private key headers
+static SSH_KEY_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r#"(?P<key>(?:ssh-(?:rsa|dss|ed25519)|ecdsa-sha2-nistp(?:256|384|521)|sk-(?:ssh-ed25519|ecdsa-sha2-nistp256)@openssh\.com)\s+[A-Za-z0-9+/]{50,}={0,3})(?:[ \t]+(?P<comment>[^"'\s][^"'\r\n]*))?|-----BEGIN\s+(?:RSA|DSA|EC|OPENSSH)\s+(?:PRIVATE|PUBLIC)\s+KEY-----"#).unwrap());
 
 // Hash detection - exact lengths for different hash types
 static MD5_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"\b[a-fA-F0-9]{32}\b").unwrap());
 static SHA1_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"\b[a-fA-F0-9]{40}\b").unwrap());
+static SHA224_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"\b[a-fA-F0-9]{56}\b").unwrap());
 static SHA256_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"\b[a-fA-F0-9]{64}\b").unwrap());
+static SHA384_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"\b[a-fA-F0-9]{96}\b").unwrap());
 static SHA512_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"\b[a-fA-F0-9]{128}\b").unwrap());
 
+// Extra facts about a value (e.g. the scope of an IP address), as ordered key/value pairs
+type Attributes = Vec<(&'static str, String)>;
+
+// A single classified value and the byte offset where it starts within its string
+#[derive(Debug, Clone)]
+struct Match {
+    string_type: StringType,
+    value: String,
+    offset: usize,
+    attrs: Attributes,
+}
+
+impl Match {
+    fn new(string_type: StringType, value: &str, offset: usize) -> Self {
+        Match { string_type, value: value.to_string(), offset, attrs: Vec::new() }
+    }
+
+    fn with_attr(mut self, key: &'static str, value: impl Into<String>) -> Self {
+        self.attrs.push((key, value.into()));
+        self
+    }
+
+    fn with_attrs(mut self, attrs: Attributes) -> Self {
+        self.attrs.extend(attrs);
+        self
+    }
+
+    fn attr(&self, key: &str) -> Option<&str> {
+        self.attrs.iter().find(|(k, _)| *k == key).map(|(_, v)| v.as_str())
+    }
+
+    fn overlaps(&self, start: usize, end: usize) -> bool {
+        self.offset < end && start < self.offset + self.value.len()
+    }
+}
+
+// Where a collected value was seen: the source it came from, the byte offset of the
+// containing string in that source and the offset of the value within the string
+#[derive(Debug, Clone)]
+struct Location {
+    source: Rc<str>,
+    string_offset: usize,
+    match_offset: usize,
+}
+
+impl Location {
+    fn describe(&self) -> String {
+        format!("{}@0x{:x}+{}", self.source, self.string_offset, self.match_offset)
+    }
+}
+
+// Everything known about one distinct value: where it was seen and its attributes
+#[derive(Debug, Default)]
+struct Finding {
+    locations: Vec<Location>,
+    attrs: Attributes,
+}
+
+impl Finding {
+    fn attr(&self, key: &str) -> Option<&str> {
+        self.attrs.iter().find(|(k, _)| *k == key).map(|(_, v)| v.as_str())
+    }
+
+    // Distinct sources in the order they were first seen
+    fn sources(&self) -> Vec<&str> {
+        let mut sources: Vec<&str> = Vec::new();
+        for location in &self.locations {
+            if !sources.contains(&&*location.source) {
+                sources.push(&location.source);
+            }
+        }
+        sources
+    }
+}
+
+// Every distinct value per type, with everything known about it
+type Collections = HashMap<StringType, HashMap<String, Finding>>;
+
 fn is_json(line: &str) -> bool {
     line.trim_start().starts_with('{') || line.trim_start().starts_with('[')
 }
@@ -186,7 +339,7 @@ fn is_cpp_exception(line: &str) -> bool {
 fn is_python_traceback(line: &str) -> bool {
     line.contains("Traceback (most recent call last)") ||
     line.contains("File \"") && line.contains(", line ") ||
-    line.contains("Error:") && line.chars().next().map_or(false, |c| c.is_uppercase())
+    line.contains("Error:") && line.chars().next().is_some_and(|c| c.is_uppercase())
 }
 
 fn is_java_stacktrace(line: &str) -> bool {
@@ -220,112 +373,190 @@ fn is_junk(line: &str) -> bool {
     non_printable_count > line.len() / 4
 }
 
-fn extract_all_matches(line: &str) -> Vec<(StringType, String)> {
+fn extract_all_matches(line: &str) -> Vec<Match> {
     use StringType::*;
     let mut matches = Vec::new();
 
     // Extract regex-based patterns
     for mat in URL_REGEX.find_iter(line) {
-        matches.push((URLString, mat.as_str().to_string()));
+        let Some(url) = urls::parse_url(mat.as_str()) else {
+            continue;
+        };
+        // Credentials passed as query parameters (?api_key=...) are secrets in their own right
+        for (name, value, offset) in url.secret_params {
+            matches.push(Match::new(SecretString, &value, mat.start() + offset).with_attr("param", name).with_attr("validation", secrets::FORMAT_ONLY));
+        }
+        matches.push(Match::new(URLString, &url.text, mat.start()).with_attrs(url.attrs));
+    }
+    for conn in connstr::find_connection_strings(line) {
+        // An embedded password is reported as a secret as well, unless a query parameter
+        // secret of the URL already covers it
+        if let Some((password, offset)) = &conn.password {
+            let start = conn.offset + offset;
+            if !matches.iter().any(|m| m.string_type == SecretString && m.overlaps(start, start + password.len())) {
+                matches.push(Match::new(SecretString, password, start).with_attr("key", "password").with_attr("validation", secrets::FORMAT_ONLY));
+            }
+        }
+        matches.push(Match::new(ConnectionString, &conn.text, conn.offset).with_attrs(conn.attrs));
     }
     for mat in EMAIL_REGEX.find_iter(line) {
-        matches.push((EmailString, mat.as_str().to_string()));
+        matches.push(Match::new(EmailString, mat.as_str(), mat.start()));
+    }
+    for (offset, domain) in domain::find_domains(line) {
+        matches.push(Match::new(DomainString, &domain, offset));
     }
     for mat in UUID_REGEX.find_iter(line) {
-        matches.push((UUIDString, mat.as_str().to_string()));
+        matches.push(Match::new(UUIDString, mat.as_str(), mat.start()));
     }
     for mat in MAC_REGEX.find_iter(line) {
-        matches.push((MACAddressString, mat.as_str().to_string()));
+        matches.push(Match::new(MACAddressString, mat.as_str(), mat.start()));
     }
     for mat in IPV4_REGEX.find_iter(line) {
-        matches.push((IPv4String, mat.as_str().to_string()));
+        if network::is_valid_ipv4(line, mat.start(), mat.end()) {
+            let scope = network::ipv4_scope(mat.as_str().parse().unwrap());
+            matches.push(Match::new(IPv4String, mat.as_str(), mat.start()).with_attr("scope", scope));
+        }
     }
-    for mat in IPV6_REGEX.find_iter(line) {
-        matches.push((IPv6String, mat.as_str().to_string()));
+    for (offset, address, parsed) in network::find_ipv6(line) {
+        matches.push(Match::new(IPv6String, &address, offset).with_attr("scope", network::ipv6_scope(parsed)));
     }
     for mat in TIMESTAMP_REGEX.find_iter(line) {
-        matches.push((TimestampString, mat.as_str().to_string()));
+        matches.push(Match::new(TimestampString, mat.as_str(), mat.start()));
     }
     for mat in SEMVER_REGEX.find_iter(line) {
-        matches.push((SemVerString, mat.as_str().to_string()));
+        matches.push(Match::new(SemVerString, mat.as_str(), mat.start()));
     }
     for mat in GIT_HASH_REGEX.find_iter(line) {
-        matches.push((GitHashString, mat.as_str().to_string()));
+        matches.push(Match::new(GitHashString, mat.as_str(), mat.start()));
     }
     for mat in BASE64_REGEX.find_iter(line) {
-        matches.push((Base64String, mat.as_str().to_string()));
+        matches.push(Match::new(Base64String, mat.as_str(), mat.start()));
     }
     for mat in HEX_REGEX.find_iter(line) {
-        matches.push((HexString, mat.as_str().to_string()));
+        matches.push(Match::new(HexString, mat.as_str(), mat.start()));
     }
     for mat in CPP_TEMPLATE_REGEX.find_iter(line) {
-        matches.push((CppTemplateString, mat.as_str().to_string()));
+        matches.push(Match::new(CppTemplateString, mat.as_str(), mat.start()));
     }
     for mat in CPP_MANGLED_REGEX.find_iter(line) {
-        matches.push((CppRTTIString, mat.as_str().to_string()));
+        matches.push(Match::new(CppRTTIString, mat.as_str(), mat.start()));
     }
     for mat in SQL_REGEX.find_iter(line) {
-        matches.push((SQLQueryString, mat.as_str().to_string()));
+        matches.push(Match::new(SQLQueryString, mat.as_str(), mat.start()));
     }
     for mat in PATH_REGEX.find_iter(line) {
-        matches.push((PathString, mat.as_str().to_string()));
+        matches.push(Match::new(PathString, mat.as_str(), mat.start()));
     }
     for mat in FORMAT_REGEX.find_iter(line) {
-        matches.push((FormatMessageString, mat.as_str().to_string()));
-    }
-    for mat in SECRET_REGEX.find_iter(line) {
-        matches.push((SecretString, mat.as_str().to_string()));
-    }
-    for mat in SSH_KEY_REGEX.find_iter(line) {
-        matches.push((SSHKeyString, mat.as_str().to_string()));
-    }
-
-    // Hash detection - check in order of specificity (longest first to avoid shorter hashes matching longer ones)
-    for mat in SHA512_REGEX.find_iter(line) {
-        matches.push((SHA512HashString, mat.as_str().to_string()));
-    }
-    for mat in SHA256_REGEX.find_iter(line) {
-        matches.push((SHA256HashString, mat.as_str().to_string()));
-    }
-    for mat in SHA1_REGEX.find_iter(line) {
-        matches.push((SHA1HashString, mat.as_str().to_string()));
+        matches.push(Match::new(FormatMessageString, mat.as_str(), mat.start()));
+    }
+    for secret in secrets::find_secrets(line) {
+        // JWTs also get their header and claims
+        let claims = if secret.provider == "jwt" { jwt::decode(&secret.value).unwrap_or_default() } else { Vec::new() };
+        matches.push(
+            Match::new(SecretString, &secret.value, secret.offset)
+                .with_attr("provider", secret.provider)
+                .with_attr("kind", secret.kind)
+                .with_attr("validation", secret.validation)
+                .with_attrs(claims),
+        );
+    }
+    // Values assigned to credential-like keys, unless already reported by format or context above
+    for secret in secrets::find_keyword_secrets(line) {
+        let end = secret.offset + secret.value.len();
+        if matches.iter().any(|m| m.string_type == SecretString && m.overlaps(secret.offset, end)) {
+            continue;
+        }
+        let mut m = Match::new(SecretString, &secret.value, secret.offset).with_attr("key", secret.key);
+        if let Some(scheme) = secret.scheme {
+            m = m.with_attr("scheme", scheme);
+        }
+        matches.push(m.with_attr("validation", secrets::FORMAT_ONLY));
+    }
+    for caps in SSH_KEY_REGEX.captures_iter(line) {
+        let Some(key) = caps.name("key") else {
+            let header = caps.get(0).unwrap();
+            matches.push(Match::new(SSHKeyString, header.as_str(), header.start()));
+            continue;
+        };
+        // Public keys are decoded for their type, size and fingerprint, like ssh-keygen -l
+        let mut m = Match::new(SSHKeyString, key.as_str(), key.start()).with_attrs(sshkey::describe(key.as_str()).unwrap_or_default());
+        if let Some(comment) = caps.name("comment") {
+            m = m.with_attr("comment", comment.as_str().trim_end());
+        }
+        matches.push(m);
+    }
+
+    // Hash detection - every algorithm whose digest has this length is reported;
+    // hashes::resolve_overlaps picks one
+    let hex_digests: [(&Regex, &[StringType]); 6] = [
+        (&SHA512_REGEX, &[SHA512HashString, BLAKE2HashString]),
+        (&SHA384_REGEX, &[SHA384HashString]),
+        (&SHA256_REGEX, &[SHA256HashString, BLAKE2HashString, BLAKE3HashString]),
+        (&SHA224_REGEX, &[SHA224HashString]),
+        (&SHA1_REGEX, &[SHA1HashString]),
+        (&MD5_REGEX, &[MD5HashString, NTLMHashString]),
+    ];
+    for (regex, types) in hex_digests {
+        for mat in regex.find_iter(line) {
+            for &string_type in types {
+                matches.push(Match::new(string_type, mat.as_str(), mat.start()));
+            }
+        }
     }
-    for mat in MD5_REGEX.find_iter(line) {
-        matches.push((MD5HashString, mat.as_str().to_string()));
+    matches.extend(hashes::find_digests(line));
+    matches.extend(hashes::find_password_hashes(line));
+
+    // Random looking tokens nothing more specific has claimed
+    for token in entropy::find_high_entropy(line) {
+        let end = token.offset + token.value.len();
+        let claimed = matches.iter().any(|m| {
+            (matches!(m.string_type, SecretString | SSHKeyString | UUIDString)
+                || StringType::hash_types().contains(&m.string_type)
+                || StringType::password_hash_types().contains(&m.string_type))
+                && m.overlaps(token.offset, end)
+        });
+        if !claimed {
+            matches.push(
+                Match::new(HighEntropyString, &token.value, token.offset)
+                    .with_attr("charset", token.charset)
+                    .with_attr("entropy", format!("{:.2}", token.entropy)),
+            );
+        }
     }
 
     // For non-regex patterns that need full line context, add the full line
     if is_cpp_exception(line) {
-        matches.push((CppExceptionString, line.to_string()));
+        matches.push(Match::new(CppExceptionString, line, 0));
     }
     if is_python_traceback(line) {
-        matches.push((PythonTracebackString, line.to_string()));
+        matches.push(Match::new(PythonTracebackString, line, 0));
     }
     if is_java_stacktrace(line) {
-        matches.push((JavaStackTraceString, line.to_string()));
+        matches.push(Match::new(JavaStackTraceString, line, 0));
     }
     if is_javascript_error(line) {
-        matches.push((JavaScriptErrorString, line.to_string()));
+        matches.push(Match::new(JavaScriptErrorString, line, 0));
     }
     if is_go_panic(line) {
-        matches.push((GoPanicString, line.to_string()));
+        matches.push(Match::new(GoPanicString, line, 0));
     }
     if is_rust_panic(line) {
-        matches.push((RustPanicString, line.to_string()));
+        matches.push(Match::new(RustPanicString, line, 0));
     }
     if is_json(line) {
-        matches.push((JSONString, line.to_string()));
+        matches.push(Match::new(JSONString, line, 0));
     }
     if is_xml(line) {
-        matches.push((XMLString, line.to_string()));
+        matches.push(Match::new(XMLString, line, 0));
     }
     if is_junk(line) {
-        matches.push((JunkString, line.to_string()));
+        matches.push(Match::new(JunkString, line, 0));
     }
 
     // If no matches found, it's a regular string
     if matches.is_empty() {
-        matches.push((RegularString, line.to_string()));
+        matches.push(Match::new(RegularString, line, 0));
     }
 
     matches
@@ -334,16 +565,17 @@ fn extract_all_matches(line: &str) -> Vec<(StringType, String)> {
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, ValueEnum)]
 #[clap(rename_all = "kebab-case")]
 enum TypeFilter {
-    Junk, Regular, Ipv4, Ipv6, Path, FormatMessage, Secret, Url, Email,
+    Junk, Regular, Ipv4, Ipv6, Path, FormatMessage, Secret, HighEntropy, Url, Email, Domain,
     Uuid, MacAddress, Base64, Hex, GitHash, Json, Xml, Timestamp, SemVer,
     CppTemplate, CppException, CppRtti, PythonTraceback, JavaStackTrace,
-    JavascriptError, GoPanic, RustPanic, SqlQuery, SshKey, Md5, Sha1, Sha256, Sha512,
+    JavascriptError, GoPanic, RustPanic, SqlQuery, SshKey, PemBlock, Certificate, EncodedFile, ConnectionString, Md5, Sha1, Sha256, Sha512,
+    Sha224, Sha384, Blake2, Blake3, Ntlm, Digest, PasswordHash,
     // Group filters
-    Cpp, Errors, Network, Identifiers, DataFormats,
+    Cpp, Errors, Network, Identifiers, DataFormats, Hashes, PasswordHashes,
 }
 
 impl TypeFilter {
-    fn to_string_types(&self) -> Vec<StringType> {
+    fn to_string_types(self) -> Vec<StringType> {
         use StringType::*;
         match self {
             TypeFilter::Junk => vec![JunkString],
@@ -353,8 +585,10 @@ impl TypeFilter {
             TypeFilter::Path => vec![PathString],
             TypeFilter::FormatMessage => vec![FormatMessageString],
             TypeFilter::Secret => vec![SecretString],
+            TypeFilter::HighEntropy => vec![HighEntropyString],
             TypeFilter::Url => vec![URLString],
             TypeFilter::Email => vec![EmailString],
+            TypeFilter::Domain => vec![DomainString],
             TypeFilter::Uuid => vec![UUIDString],
             TypeFilter::MacAddress => vec![MACAddressString],
             TypeFilter::Base64 => vec![Base64String],
@@ -374,34 +608,112 @@ impl TypeFilter {
             TypeFilter::RustPanic => vec![RustPanicString],
             TypeFilter::SqlQuery => vec![SQLQueryString],
             TypeFilter::SshKey => vec![SSHKeyString],
+            TypeFilter::PemBlock => vec![PemBlockString],
+            TypeFilter::Certificate => vec![CertificateString],
+            TypeFilter::EncodedFile => vec![EncodedFileString],
+            TypeFilter::ConnectionString => vec![ConnectionString],
             TypeFilter::Md5 => vec![MD5HashString],
             TypeFilter::Sha1 => vec![SHA1HashString],
             TypeFilter::Sha256 => vec![SHA256HashString],
             TypeFilter::Sha512 => vec![SHA512HashString],
+            TypeFilter::Sha224 => vec![SHA224HashString],
+            TypeFilter::Sha384 => vec![SHA384HashString],
+            TypeFilter::Blake2 => vec![BLAKE2HashString],
+            TypeFilter::Blake3 => vec![BLAKE3HashString],
+            TypeFilter::Ntlm => vec![NTLMHashString],
+            TypeFilter::Digest => vec![DigestString],
+            TypeFilter::PasswordHash => vec![PasswordHashString],
             // Groups
             TypeFilter::Cpp => StringType::cpp_types(),
             TypeFilter::Errors => StringType::error_types(),
             TypeFilter::Network => StringType::network_types(),
             TypeFilter::Identifiers => StringType::identifier_types(),
             TypeFilter::DataFormats => StringType::data_format_types(),
+            TypeFilter::Hashes => StringType::hash_types(),
+            TypeFilter::PasswordHashes => StringType::password_hash_types(),
         }
     }
 }
 
 #[derive(Parser, Debug)]
 #[command(name = "strbin")]
-#[command(about = "Classify and summarize strings from stdin", long_about = None)]
+#[command(about = "Classify and summarize strings from files or stdin", long_about = None)]
 struct Args {
+    #[arg(help = "Files to extract strings from (reads lines from stdin if none given)")]
+    files: Vec<PathBuf>,
+
+    // Input selection
+    #[arg(short, long, help = "Scan directories given as FILES recursively")]
+    recursive: bool,
+
+    #[arg(long, value_name = "GLOB", help = "Only scan files matching this glob when recursing (can be specified multiple times)")]
+    include: Vec<String>,
+
+    #[arg(long, value_name = "GLOB", help = "Skip files matching this glob when recursing (can be specified multiple times)")]
+    exclude: Vec<String>,
+
+    #[arg(short = 'L', long, help = "Follow symbolic links when recursing (skipped by default)")]
+    follow_symlinks: bool,
+
+    // Extraction
+    #[arg(short = 'n', long, default_value_t = 4, help = "Minimum length of printable runs extracted from files")]
+    min_len: usize,
+
+    #[arg(short = 'e', long, value_enum, help = "Encodings to extract from files (can be specified multiple times, default: all)")]
+    encoding: Vec<Encoding>,
+
     // Output mode
+    #[arg(long, value_enum, default_value_t = OutputFormat::Text, help = "Output format")]
+    format: OutputFormat,
+
     #[arg(long, help = "Only show counts and types, not full strings")]
     analyze: bool,
 
+    #[arg(long, help = "Show where each string was found (source@string_offset+match_offset)")]
+    offsets: bool,
+
+    #[arg(long, value_name = "CATEGORY", help = "Only report URLs in this scheme category, e.g. database, cloud-storage, custom (can be specified multiple times)")]
+    url_category: Vec<String>,
+
+    #[arg(long, value_name = "SCHEME=CATEGORY", help = "Add or override a URL scheme category (can be specified multiple times)")]
+    url_scheme: Vec<String>,
+
+    #[arg(long, help = "Group URLs by host in text output")]
+    group_by_host: bool,
+
+    #[arg(long, help = "Also show per-file counts when scanning several files")]
+    per_file: bool,
+
     #[arg(long, help = "Maximum number of items to show per type (0 = unlimited)")]
     max_items: Option<usize>,
 
     #[arg(long, help = "Disable default filters (by default, noisy error types like Rust/Python/Java/JS/Go panics and C++ exceptions are excluded)")]
     no_defaults: bool,
 
+    #[arg(long, help = "Only report IPv4/IPv6 addresses in public (globally routable) ranges")]
+    public_ips_only: bool,
+
+    #[arg(long, help = "Drop secrets whose embedded checksum or structure is invalid")]
+    skip_invalid_secrets: bool,
+
+    #[arg(long, help = "Report a hex string under every type it could be (MD5, SHA1, GitHash, Hex, ...) instead of only the most likely one")]
+    allow_overlaps: bool,
+
+    #[arg(long, help = "Decode Base64 and Hex values (and gzip/zlib inside them) and classify what they contain")]
+    decode: bool,
+
+    #[arg(long, value_name = "N", default_value_t = 3, help = "How many encodings deep --decode goes")]
+    decode_depth: usize,
+
+    #[arg(long, value_name = "BITS", help = "Minimum Shannon entropy (bits per character) for HighEntropy strings, replacing the per-charset defaults")]
+    min_entropy: Option<f64>,
+
+    #[arg(long, help = "Mask secrets, SSH keys and embedded passwords in the output (first characters plus a short SHA-256)")]
+    redact: bool,
+
+    #[arg(long, value_name = "PATH", help = "Annotate public IPs with country/ASN from a local MaxMind .mmdb file (can be specified multiple times)")]
+    geoip_db: Vec<PathBuf>,
+
     #[arg(long, value_enum, help = "Include only these types (can be specified multiple times)")]
     with: Vec<TypeFilter>,
 
@@ -448,79 +760,259 @@ impl Args {
     }
 }
 
-fn print_summary(collections: &HashMap<StringType, HashSet<String>>, analyze: bool, max_items: Option<usize>) {
-    let mut sorted_types: Vec<_> = collections.iter().collect();
-    sorted_types.sort_by_key(|(t, _)| t.as_str());
-
-    for (string_type, strings) in sorted_types {
-        if !strings.is_empty() {
-            println!("\n{} [{}]:", string_type.as_str(), strings.len());
-
-            if !analyze {
-                let limit = max_items.unwrap_or(0);
-                let mut count = 0;
-
-                for s in strings {
-                    if limit > 0 && count >= limit {
-                        println!("  ... ({} more)", strings.len() - limit);
-                        break;
-                    }
-                    println!("  {}", s);
-                    count += 1;
+fn record_match(m: Match, source: &Rc<str>, string_offset: usize, args: &Args, collections: &mut Collections) {
+    // Skip if this type should not be included
+    if !args.should_include_type(m.string_type) {
+        return;
+    }
+    if args.public_ips_only
+        && matches!(m.string_type, StringType::IPv4String | StringType::IPv6String)
+        && m.attr("scope") != Some(network::PUBLIC_SCOPE)
+    {
+        return;
+    }
+    if args.skip_invalid_secrets && m.attr("validation") == Some(secrets::INVALID) {
+        return;
+    }
+    if m.string_type == StringType::URLString
+        && !args.url_category.is_empty()
+        && !args.url_category.iter().any(|c| Some(c.as_str()) == m.attr("category"))
+    {
+        return;
+    }
+
+    let finding = collections
+        .entry(m.string_type)
+        .or_default()
+        .entry(m.value)
+        .or_default();
+    if finding.locations.is_empty() {
+        finding.attrs = m.attrs;
+    }
+    finding.locations.push(Location { source: Rc::clone(source), string_offset, match_offset: m.offset });
+}
+
+// `wide` strings are UTF-16, two bytes per character, which match offsets are scaled by so they
+// stay byte offsets into the source
+fn collect_matches(text: &str, source: &Rc<str>, string_offset: usize, wide: bool, args: &Args, collections: &mut Collections) {
+    collect_decoded_matches(text, source, string_offset, wide, None, args, collections);
+}
+
+// Where text decoded by --decode came from: the offset of the outermost encoded value in its
+// string, and the decodings applied so far
+type DecodeOrigin<'a> = Option<(usize, &'a [&'static str])>;
+
+// Findings in decoded text are placed at the encoded value and carry the whole decode chain,
+// ending in their own type, e.g. "base64 -> gzip -> URL"
+fn with_decode_chain(mut m: Match, offset: usize, chain: &[&str], last: &str) -> Match {
+    m.offset = offset;
+    let chain = chain.iter().copied().chain([last]).collect::<Vec<_>>().join(" -> ");
+    m.with_attr("decode-chain", chain)
+}
+
+// Returns whether a credential was found, in the text itself or anything decoded from it
+fn collect_decoded_matches(text: &str, source: &Rc<str>, string_offset: usize, wide: bool, origin: DecodeOrigin, args: &Args, collections: &mut Collections) -> bool {
+    let unit = if wide { 2 } else { 1 };
+    let mut matches = extract_all_matches(text);
+
+    // Decode Base64 and Hex candidates (trying hex first where both match) while under the depth limit
+    let mut decoded = Vec::new();
+    let depth = origin.map_or(0, |(_, chain)| chain.iter().filter(|layer| matches!(**layer, "base64" | "hex")).count());
+    if args.decode && depth < args.decode_depth {
+        let mut encoded: Vec<&Match> = matches
+            .iter()
+            .filter(|m| matches!(m.string_type, StringType::HexString | StringType::Base64String))
+            .collect();
+        encoded.sort_by_key(|m| (m.offset, m.string_type != StringType::HexString));
+        let mut spans = HashSet::new();
+        for m in encoded {
+            if spans.contains(&(m.offset, m.value.len())) {
+                continue;
+            }
+            if let Some(d) = decode::decode(m.string_type, &m.value) {
+                spans.insert((m.offset, m.value.len()));
+                decoded.push((m.offset, m.value.len(), d));
+            }
+        }
+    }
+
+    // Decoded content goes first, so that with --redact an encoded value that turned out to
+    // hold a secret can be masked along with it
+    let mut found_secret = matches.iter().any(|m| redact::is_secret(m.string_type));
+    let mut carriers = Vec::new();
+    for (carrier_offset, carrier_len, d) in decoded {
+        let (offset, chain) = origin.unwrap_or((carrier_offset, &[]));
+        let chain: Vec<&'static str> = chain.iter().chain(&d.chain).copied().collect();
+        let mut secret = false;
+        match d.payload {
+            decode::Payload::Text(text) => {
+                for line in text.lines() {
+                    secret |= collect_decoded_matches(line, source, string_offset, wide, Some((offset, &chain)), args, collections);
                 }
             }
+            decode::Payload::Certificate(certificate) => {
+                let m = Match::new(StringType::CertificateString, &certificate.fingerprint, 0).with_attrs(certificate.attrs);
+                record_match(with_decode_chain(m, offset * unit, &chain, StringType::CertificateString.as_str()), source, string_offset, args, collections);
+            }
+            decode::Payload::File { format, size, sha256 } => {
+                let m = Match::new(StringType::EncodedFileString, &sha256, 0)
+                    .with_attr("format", format)
+                    .with_attr("size", size.to_string());
+                record_match(with_decode_chain(m, offset * unit, &chain, StringType::EncodedFileString.as_str()), source, string_offset, args, collections);
+            }
+        }
+        if secret {
+            carriers.push((carrier_offset, carrier_offset + carrier_len));
+            found_secret = true;
+        }
+    }
+
+    if !args.allow_overlaps {
+        matches = hashes::resolve_overlaps(matches, text, |t| args.should_include_type(t));
+    }
+    if args.redact {
+        matches = redact::mask_overlaps(matches, text, &carriers);
+    }
+    for mut m in matches {
+        if let Some((offset, chain)) = origin {
+            let last = m.string_type.as_str().to_string();
+            m = with_decode_chain(m, offset, chain, &last);
         }
+        m.offset *= unit;
+        record_match(m, source, string_offset, args, collections);
     }
+    found_secret
 }
 
-fn print_final_summary(collections: &HashMap<StringType, HashSet<String>>) {
-    let mut all_types: Vec<_> = collections.keys().collect();
-    all_types.sort_by_key(|t| t.as_str());
-
-    if !all_types.is_empty() {
-        println!("\n\n=== Summary ===");
-        println!("Detected types: {}", all_types.iter().map(|t| t.as_str()).collect::<Vec<_>>().join(", "));
-        println!("\nTo filter, use:");
-        println!("  --with <type>     (include only specific types, can repeat)");
-        println!("  --without <type>  (exclude specific types, can repeat)");
-        println!("  --analyze         (show only counts)");
-        println!("  --max-items N     (limit items per type)");
-        println!("  --no-defaults     (disable default filters)");
-        println!("\nExamples:");
-        println!("  --with url --with email");
-        println!("  --without errors --without cpp");
+// Lines go through the usual classification, complete PEM blocks are recorded whole
+fn collect_pem_items(items: Vec<pem::PemItem>, source: &Rc<str>, args: &Args, collections: &mut Collections) {
+    for item in items {
+        match item {
+            pem::PemItem::Line(text, offset, wide) => collect_matches(&text, source, offset, wide, args, collections),
+            pem::PemItem::Block(block) => {
+                let mut m = Match::new(StringType::PemBlockString, &block.text, 0)
+                    .with_attr("label", block.label)
+                    .with_attr("kind", block.kind);
+                if block.encrypted {
+                    m = m.with_attr("encrypted", "true");
+                }
+                if block.kind == "certificate"
+                    && let Some(certificate) = cert::from_pem(&block.text)
+                {
+                    let c = Match::new(StringType::CertificateString, &certificate.fingerprint, 0).with_attrs(certificate.attrs);
+                    record_match(c, source, block.offset, args, collections);
+                }
+                record_match(m, source, block.offset, args, collections);
+            }
+        }
     }
 }
 
 fn main() {
     let args = Args::parse();
-
-    let stdin = io::stdin();
-    let reader = BufReader::with_capacity(4096 * 4096, stdin);
-    let mut collections: HashMap<StringType, HashSet<String>> = HashMap::new();
-
-    for line in reader.lines() {
-        match line {
-            Ok(text) => {
-                // Extract all matches from this line
-                let matches = extract_all_matches(&text);
-
-                for (string_type, matched_text) in matches {
-                    // Skip if this type should not be included
-                    if !args.should_include_type(string_type) {
-                        continue;
-                    }
-
-                    collections
-                        .entry(string_type)
-                        .or_insert_with(HashSet::new)
-                        .insert(matched_text);
+    let mut collections: Collections = HashMap::new();
+
+    if let Err(e) = urls::configure_schemes(&args.url_scheme) {
+        eprintln!("Error: {}", e);
+        std::process::exit(2);
+    }
+    entropy::configure(args.min_entropy);
+
+    let geoip = if args.geoip_db.is_empty() {
+        None
+    } else {
+        match geoip::GeoIp::open(&args.geoip_db) {
+            Ok(geoip) => Some(geoip),
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                std::process::exit(2);
+            }
+        }
+    };
+
+    // Inputs that couldn't be read are reported as they come up, and in the exit status at the end
+    let mut failed = false;
+    if args.files.is_empty() {
+        let stdin = io::stdin();
+        let reader = BufReader::with_capacity(4096 * 4096, stdin);
+        let source: Rc<str> = Rc::from("<stdin>");
+        let mut offset = 0;
+        let mut pem = pem::PemCollector::default();
+
+        // Split on raw bytes rather than using lines() so offsets stay exact
+        for line in reader.split(b'\n') {
+            match line {
+                Ok(bytes) => {
+                    let consumed = bytes.len() + 1;
+                    let text = String::from_utf8_lossy(&bytes);
+                    let text = text.strip_suffix('\r').unwrap_or(&text);
+                    collect_pem_items(pem.feed(text, offset, offset + bytes.len(), false), &source, &args, &mut collections);
+                    offset += consumed;
                 }
+                Err(e) => {
+                    eprintln!("Error: {}", e);
+                    failed = true;
+                }
+            }
+        }
+        collect_pem_items(pem.finish(), &source, &args, &mut collections);
+    } else {
+        let filter = match walk::FileFilter::new(&args.include, &args.exclude) {
+            Ok(filter) => filter,
+            Err(e) => {
+                eprintln!("Error: invalid glob: {}", e);
+                std::process::exit(2);
+            }
+        };
+
+        let (files, walk_failed) = walk::collect_files(&args.files, args.recursive, args.follow_symlinks, &filter);
+        failed = walk_failed;
+        for path in files {
+            let data = match fs::read(&path) {
+                Ok(data) => data,
+                Err(e) => {
+                    eprintln!("Error: {}: {}", path.display(), e);
+                    failed = true;
+                    continue;
+                }
+            };
+            let source: Rc<str> = Rc::from(path.display().to_string());
+
+            let mut pem = pem::PemCollector::default();
+            for run in extract::extract_strings(&data, args.min_len, &args.encoding) {
+                let items = pem.feed(&run.text, run.offset, run.offset + run.byte_len(), run.wide);
+                collect_pem_items(items, &source, &args, &mut collections);
+            }
+            collect_pem_items(pem.finish(), &source, &args, &mut collections);
+
+            // Certificates embedded in binary form never show up as text
+            for (offset, certificate) in cert::find_der_certificates(&data) {
+                let m = Match::new(StringType::CertificateString, &certificate.fingerprint, 0).with_attrs(certificate.attrs);
+                record_match(m, &source, offset, &args, &mut collections);
             }
-            Err(e) => eprintln!("Error: {}", e),
         }
     }
 
-    print_summary(&collections, args.analyze, args.max_items);
-    print_final_summary(&collections);
+    if let Some(geoip) = &geoip {
+        geoip.enrich(&mut collections);
+    }
+    if args.redact {
+        redact::redact(&mut collections);
+    }
+
+    match args.format {
+        OutputFormat::Text => {
+            output::print_summary(&collections, args.analyze, args.max_items, args.offsets, args.group_by_host);
+            if args.per_file {
+                output::print_per_file_summary(&collections);
+            }
+            output::print_final_summary(&collections);
+        }
+        OutputFormat::Json => output::print_json(&collections, args.analyze, args.max_items),
+        OutputFormat::Jsonl => output::print_jsonl(&collections, args.max_items),
+        OutputFormat::Csv | OutputFormat::Tsv => output::print_delimited(&collections, args.max_items, args.format),
+    }
+    if failed {
+        std::process::exit(1);
+    }
 }
@@ -0,0 +1,171 @@
+use std::net::{Ipv4Addr, Ipv6Addr};
+use regex::Regex;
+use once_cell::sync::Lazy;
+
+// Loose IPv6 candidate: a run of hex digits, colons and dots with at least two colons, plus an
+// optional zone ID. Everything else is decided by actually parsing it.
+static IPV6_CANDIDATE_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?i)[0-9a-f.]*:[0-9a-f.]*:[0-9a-f:.]*(?:%[0-9a-z_.\-]+)?").unwrap());
+
+// Words that, right before a dotted quad, mean it's a version number and not an address
+const VERSION_WORDS: &[&str] = &[
+    "v", "ver", "version", "rev", "revision", "release", "build", "firmware", "fw",
+    "openssl", "libssl", "libcrypto", "zlib", "libpng", "libjpeg", "glibc", "libc", "gcc",
+    "clang", "llvm", "python", "perl", "ruby", "php", "java", "jdk", "jre", "node", "curl",
+    "libcurl", "sqlite", "boost", "qt", "icu", "kernel", "linux", "windows", "darwin",
+    "mozilla", "chrome", "firefox", "safari", "applewebkit", "gecko", "msie", "trident",
+    "edge", "android", "ios", "nginx", "apache", "openssh", "busybox", "uclibc", "musl",
+];
+
+// The word (if any) directly in front of `start`, skipping separators like "v1.2", "zlib-1.2",
+// "Chrome/1.2" or "version: 1.2"
+fn preceding_word(line: &str, start: usize) -> String {
+    let before = line[..start].trim_end_matches([' ', '\t', '/', '-', '_', ':', '=', '(', '.']);
+    let word_start = before
+        .char_indices()
+        .rev()
+        .find(|(_, c)| !c.is_ascii_alphanumeric())
+        .map_or(0, |(i, c)| i + c.len_utf8());
+    before[word_start..].to_ascii_lowercase()
+}
+
+fn looks_like_version(line: &str, start: usize, end: usize) -> bool {
+    // Part of a longer dotted number such as 1.2.3.4.5
+    let after = &line[end..];
+    if after.starts_with('.') && after[1..].starts_with(|c: char| c.is_ascii_digit()) {
+        return true;
+    }
+    if line[..start].ends_with('.') && line[..start - 1].ends_with(|c: char| c.is_ascii_digit()) {
+        return true;
+    }
+
+    let word = preceding_word(line, start);
+    // Also catch trailing digits glued to the name, e.g. "python3 3.10.1.2"
+    let word = word.trim_end_matches(|c: char| c.is_ascii_digit());
+    VERSION_WORDS.contains(&word)
+}
+
+// Accept an IPv4 candidate only if every octet is in range and the context doesn't
+// make it look like a version string
+pub fn is_valid_ipv4(line: &str, start: usize, end: usize) -> bool {
+    line[start..end].parse::<Ipv4Addr>().is_ok() && !looks_like_version(line, start, end)
+}
+
+fn is_identifier_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_'
+}
+
+// Find IPv6 addresses in a line, returning each address (with its zone ID, without brackets
+// or port), the offset where it starts and the parsed address. Candidates must parse as `Ipv6Addr`, which rules
+// out timestamps and MAC addresses, and must not be glued to identifiers, which rules out
+// C++ scopes like `Foo::Bar`.
+pub fn find_ipv6(line: &str) -> Vec<(usize, String, Ipv6Addr)> {
+    let mut found = Vec::new();
+
+    for mat in IPV6_CANDIDATE_REGEX.find_iter(line) {
+        let start = mat.start();
+        let mut candidate = mat.as_str().trim_end_matches('.');
+        if candidate.ends_with(':') && !candidate.ends_with("::") {
+            candidate = &candidate[..candidate.len() - 1];
+        }
+        let end = start + candidate.len();
+
+        let before = line[..start].chars().next_back();
+        let after = line[end..].chars().next();
+        if before.is_some_and(is_identifier_char) || after.is_some_and(is_identifier_char) {
+            continue;
+        }
+        // [addr] and [addr]:port forms
+        let bracketed = before == Some('[') && after == Some(']');
+
+        let (address, zone) = match candidate.split_once('%') {
+            Some((address, zone)) => (address, Some(zone)),
+            None => (candidate, None),
+        };
+        let Ok(parsed) = address.parse::<Ipv6Addr>() else {
+            continue;
+        };
+        if zone.is_some_and(str::is_empty) {
+            continue;
+        }
+
+        // Short compressed forms like "a::b" are far more likely to be code than addresses,
+        // so unless bracketed only keep them if some group looks like a real prefix
+        let groups: Vec<&str> = address.split(':').filter(|g| !g.is_empty()).collect();
+        let plausible = bracketed
+            || groups.len() >= 3
+            || parsed == Ipv6Addr::LOCALHOST
+            || groups.iter().any(|g| g.len() >= 3);
+        if !plausible || (parsed.is_unspecified() && !bracketed) {
+            continue;
+        }
+
+        found.push((start, candidate.to_string(), parsed));
+    }
+
+    found
+}
+
+pub const PUBLIC_SCOPE: &str = "public";
+
+// Which special-purpose range (RFC 6890 and friends) an IPv4 address belongs to
+pub fn ipv4_scope(ip: Ipv4Addr) -> &'static str {
+    let [a, b, c, _] = ip.octets();
+    match (a, b, c) {
+        (0, _, _) => "unspecified",
+        (10, _, _) | (172, 16..=31, _) | (192, 168, _) => "private",
+        (100, 64..=127, _) => "cgnat",
+        (127, _, _) => "loopback",
+        (169, 254, _) => "link-local",
+        (192, 0, 2) | (198, 51, 100) | (203, 0, 113) => "documentation",
+        (198, 18..=19, _) | (192, 0, 0) => "reserved",
+        (224..=239, _, _) => "multicast",
+        _ if ip.is_broadcast() => "broadcast",
+        (240..=255, _, _) => "reserved",
+        _ => PUBLIC_SCOPE,
+    }
+}
+
+// Same for IPv6, looking through IPv4-mapped and NAT64 addresses to the embedded IPv4 one
+pub fn ipv6_scope(ip: Ipv6Addr) -> &'static str {
+    let segments = ip.segments();
+    if ip.is_unspecified() {
+        return "unspecified";
+    }
+    if ip.is_loopback() {
+        return "loopback";
+    }
+    if let Some(v4) = ip.to_ipv4_mapped() {
+        return ipv4_scope(v4);
+    }
+    if segments[..6] == [0x64, 0xff9b, 0, 0, 0, 0] {
+        let [_, _, _, _, _, _, hi, lo] = segments;
+        return ipv4_scope(Ipv4Addr::from((u32::from(hi) << 16) | u32::from(lo)));
+    }
+    match segments[0] {
+        0xfe80..=0xfebf => "link-local",
+        0xfc00..=0xfdff => "private",
+        0xff00..=0xffff => "multicast",
+        0x2001 if segments[1] == 0x0db8 => "documentation",
+        0x2000..=0x3fff => PUBLIC_SCOPE,
+        _ => "reserved",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn preceding_word_after_non_ascii() {
+        assert_eq!(preceding_word("é 1.2.3.4", 3), "");
+        assert_eq!(preceding_word("café: 8.8.8.8", 7), "");
+        assert_eq!(preceding_word("über version 1.2.3.4", 14), "version");
+        assert_eq!(preceding_word("ü openssl 1.0.2.11", 11), "openssl");
+    }
+
+    #[test]
+    fn ipv4_after_non_ascii() {
+        assert!(is_valid_ipv4("café: 8.8.8.8", 7, 14));
+        assert!(!is_valid_ipv4("é openssl 1.0.2.11", 11, 19));
+    }
+}
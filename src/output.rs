@@ -0,0 +1,271 @@
+use clap::ValueEnum;
+use std::collections::{BTreeMap, HashMap};
+use serde_json::{json, Value};
+use crate::{Attributes, Collections, Finding, Location, StringType};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+#[clap(rename_all = "kebab-case")]
+pub enum OutputFormat {
+    /// Human readable summary grouped by type
+    Text,
+    /// A single JSON document grouped by type
+    Json,
+    /// One JSON record per distinct value
+    Jsonl,
+    /// Comma separated type,value,count,source,attributes rows
+    Csv,
+    /// Tab separated type,value,count,source,attributes rows
+    Tsv,
+}
+
+fn describe_attrs(attrs: &Attributes) -> String {
+    attrs.iter().map(|(k, v)| format!("{}={}", k, v)).collect::<Vec<_>>().join(", ")
+}
+
+// Text output lists values alphabetically, except that public IP addresses come first
+// since hard-coded external infrastructure is what people look for in the network types
+fn display_order(strings: &HashMap<String, Finding>) -> SortedValues<'_> {
+    let mut values: Vec<_> = strings.iter().collect();
+    values.sort_by(|a, b| {
+        let a_public = a.1.attr("scope") == Some(crate::network::PUBLIC_SCOPE);
+        let b_public = b.1.attr("scope") == Some(crate::network::PUBLIC_SCOPE);
+        b_public.cmp(&a_public).then(a.0.cmp(b.0))
+    });
+    values
+}
+
+fn print_value(indent: &str, value: &str, finding: &Finding, offsets: bool) {
+    // Multi-line values (PEM blocks) keep their continuation lines under the first one
+    let mut line = format!("{}{}", indent, value.replace('\n', &format!("\n{}", indent)));
+    if !finding.attrs.is_empty() {
+        line.push_str(&format!("  ({})", describe_attrs(&finding.attrs)));
+    }
+    if offsets && !finding.locations.is_empty() {
+        let more = if finding.locations.len() > 1 {
+            format!(" (+{} more)", finding.locations.len() - 1)
+        } else {
+            String::new()
+        };
+        line.push_str(&format!("  [{}{}]", finding.locations[0].describe(), more));
+    }
+    println!("{}", line);
+}
+
+// URLs under one heading per host, busiest hosts first
+fn print_urls_by_host(strings: &HashMap<String, Finding>, limit: usize, offsets: bool) {
+    let mut hosts: BTreeMap<&str, SortedValues<'_>> = BTreeMap::new();
+    for (value, finding) in display_order(strings) {
+        hosts.entry(finding.attr("host").unwrap_or("(no host)")).or_default().push((value, finding));
+    }
+    let mut hosts: Vec<_> = hosts.into_iter().collect();
+    hosts.sort_by_key(|(_, urls)| std::cmp::Reverse(urls.len()));
+
+    for (host, urls) in hosts {
+        println!("  {} [{}]:", host, urls.len());
+        for (count, (value, finding)) in urls.iter().enumerate() {
+            if limit > 0 && count >= limit {
+                println!("    ... ({} more)", urls.len() - limit);
+                break;
+            }
+            // Host is already in the heading
+            let attrs = finding.attrs.iter().filter(|(k, _)| *k != "host").cloned().collect();
+            let finding = Finding { locations: finding.locations.clone(), attrs };
+            print_value("    ", value, &finding, offsets);
+        }
+    }
+}
+
+pub fn print_summary(collections: &Collections, analyze: bool, max_items: Option<usize>, offsets: bool, group_by_host: bool) {
+    let mut sorted_types: Vec<_> = collections.iter().collect();
+    sorted_types.sort_by_key(|(t, _)| t.as_str());
+
+    for (string_type, strings) in sorted_types {
+        if !strings.is_empty() {
+            println!("\n{} [{}]:", string_type.as_str(), strings.len());
+
+            if !analyze {
+                let limit = max_items.unwrap_or(0);
+
+                if group_by_host && *string_type == StringType::URLString {
+                    print_urls_by_host(strings, limit, offsets);
+                    continue;
+                }
+
+                for (count, (s, finding)) in display_order(strings).into_iter().enumerate() {
+                    if limit > 0 && count >= limit {
+                        println!("  ... ({} more)", strings.len() - limit);
+                        break;
+                    }
+                    print_value("  ", s, finding, offsets);
+                }
+            }
+        }
+    }
+}
+
+pub fn print_final_summary(collections: &Collections) {
+    let mut all_types: Vec<_> = collections.keys().collect();
+    all_types.sort_by_key(|t| t.as_str());
+
+    if !all_types.is_empty() {
+        println!("\n\n=== Summary ===");
+        println!("Detected types: {}", all_types.iter().map(|t| t.as_str()).collect::<Vec<_>>().join(", "));
+        println!("\nTo filter, use:");
+        println!("  --with <type>     (include only specific types, can repeat)");
+        println!("  --without <type>  (exclude specific types, can repeat)");
+        println!("  --analyze         (show only counts)");
+        println!("  --max-items N     (limit items per type)");
+        println!("  --offsets         (show where each string was found)");
+        println!("  --per-file        (break the counts down by input file)");
+        println!("  --public-ips-only (drop private/reserved IP addresses)");
+        println!("  --group-by-host   (list URLs under their host)");
+        println!("  --no-defaults     (disable default filters)");
+        println!("\nExamples:");
+        println!("  --with url --with email");
+        println!("  --without errors --without cpp");
+    }
+}
+
+// Distinct values found in each source, broken down by type
+pub fn print_per_file_summary(collections: &Collections) {
+    let mut per_source: BTreeMap<&str, BTreeMap<&str, usize>> = BTreeMap::new();
+
+    for (string_type, strings) in collections {
+        for finding in strings.values() {
+            for source in finding.sources() {
+                *per_source.entry(source).or_default().entry(string_type.as_str()).or_default() += 1;
+            }
+        }
+    }
+
+    if !per_source.is_empty() {
+        println!("\n\n=== Per-file ===");
+        for (source, counts) in per_source {
+            let counts: Vec<_> = counts.iter().map(|(t, n)| format!("{} [{}]", t, n)).collect();
+            println!("{}: {}", source, counts.join(", "));
+        }
+    }
+}
+
+type SortedValues<'a> = Vec<(&'a String, &'a Finding)>;
+
+// Types in name order, each with its values sorted so structured output is stable between runs
+fn sorted_collections(collections: &Collections) -> Vec<(StringType, SortedValues<'_>)> {
+    let mut sorted: Vec<_> = collections
+        .iter()
+        .map(|(t, strings)| {
+            let mut values: Vec<_> = strings.iter().collect();
+            values.sort_by(|a, b| a.0.cmp(b.0));
+            (*t, values)
+        })
+        .collect();
+    sorted.sort_by_key(|(t, _)| t.as_str().to_string());
+    sorted
+}
+
+fn location_json(location: &Location) -> Value {
+    json!({
+        "source": &*location.source,
+        "string_offset": location.string_offset,
+        "match_offset": location.match_offset,
+    })
+}
+
+fn record_json(value: &str, finding: &Finding) -> Value {
+    let attributes: serde_json::Map<_, _> = finding
+        .attrs
+        .iter()
+        .map(|(k, v)| (k.to_string(), json!(v)))
+        .collect();
+
+    json!({
+        "value": value,
+        "count": finding.locations.len(),
+        "sources": finding.sources(),
+        "attributes": attributes,
+        "locations": finding.locations.iter().map(location_json).collect::<Vec<_>>(),
+    })
+}
+
+// {"counts": {type: distinct values}, "types": {type: [{value, count, sources, attributes, locations}]}}
+pub fn print_json(collections: &Collections, analyze: bool, max_items: Option<usize>) {
+    let limit = max_items.filter(|&n| n > 0).unwrap_or(usize::MAX);
+    let mut counts = serde_json::Map::new();
+    let mut types = serde_json::Map::new();
+
+    for (string_type, values) in sorted_collections(collections) {
+        counts.insert(string_type.as_str().to_string(), json!(values.len()));
+        if !analyze {
+            let records: Vec<_> = values
+                .iter()
+                .take(limit)
+                .map(|(value, finding)| record_json(value, finding))
+                .collect();
+            types.insert(string_type.as_str().to_string(), Value::Array(records));
+        }
+    }
+
+    let mut document = serde_json::Map::new();
+    document.insert("counts".to_string(), Value::Object(counts));
+    if !analyze {
+        document.insert("types".to_string(), Value::Object(types));
+    }
+    println!("{}", Value::Object(document));
+}
+
+// One {type, value, count, sources, attributes, locations} record per line
+pub fn print_jsonl(collections: &Collections, max_items: Option<usize>) {
+    let limit = max_items.filter(|&n| n > 0).unwrap_or(usize::MAX);
+
+    for (string_type, values) in sorted_collections(collections) {
+        for (value, finding) in values.into_iter().take(limit) {
+            let mut record = record_json(value, finding);
+            record["type"] = json!(string_type.as_str());
+            println!("{}", record);
+        }
+    }
+}
+
+// RFC 4180: quote fields containing separators, quotes or line breaks, doubling inner quotes
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+// TSV has no quoting, so escape the characters that would break a row
+fn tsv_field(field: &str) -> String {
+    field
+        .replace('\\', "\\\\")
+        .replace('\t', "\\t")
+        .replace('\n', "\\n")
+        .replace('\r', "\\r")
+}
+
+// Header plus one type,value,count,source,attributes row per distinct value, with sources
+// joined with ';' and attributes written as k=v;k=v
+pub fn print_delimited(collections: &Collections, max_items: Option<usize>, format: OutputFormat) {
+    let (separator, escape): (&str, fn(&str) -> String) = match format {
+        OutputFormat::Tsv => ("\t", tsv_field),
+        _ => (",", csv_field),
+    };
+    let limit = max_items.filter(|&n| n > 0).unwrap_or(usize::MAX);
+
+    println!("{}", ["type", "value", "count", "source", "attributes"].join(separator));
+    for (string_type, values) in sorted_collections(collections) {
+        for (value, finding) in values.into_iter().take(limit) {
+            let attributes: Vec<_> = finding.attrs.iter().map(|(k, v)| format!("{}={}", k, v)).collect();
+
+            let row = [
+                escape(string_type.as_str()),
+                escape(value),
+                finding.locations.len().to_string(),
+                escape(&finding.sources().join(";")),
+                escape(&attributes.join(";")),
+            ];
+            println!("{}", row.join(separator));
+        }
+    }
+}
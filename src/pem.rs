@@ -0,0 +1,119 @@
+use regex::Regex;
+use once_cell::sync::Lazy;
+
+static BEGIN_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"^-----BEGIN ([A-Z0-9 ]+)-----$").unwrap());
+
+// Lines allowed between BEGIN and END: base64, RFC 1421 / OpenPGP armor headers (Proc-Type,
+// DEK-Info, Version, ...), the blank line after them and the OpenPGP CRC line (=AbCd)
+static BODY_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"^(?:[A-Za-z0-9+/]+={0,2}|=[A-Za-z0-9+/]{4}|[A-Za-z0-9-]+: .*|)$").unwrap());
+
+// Lines of a block must follow each other: at most a line break (\r\n, or two UTF-16 units) apart
+const MAX_LINE_GAP: usize = 4;
+
+// Upper bound on the lines held back for one block; large PGP key blocks run to a few thousand
+const MAX_BLOCK_LINES: usize = 5000;
+
+// A complete BEGIN ... END block and where its BEGIN line starts
+pub struct PemBlock {
+    pub offset: usize,
+    pub text: String,
+    pub label: String,
+    pub kind: &'static str,
+    pub encrypted: bool,
+}
+
+// What the collector hands back: a line to classify as usual (text, offset, whether it was
+// UTF-16), or a finished block
+pub enum PemItem {
+    Line(String, usize, bool),
+    Block(PemBlock),
+}
+
+struct PendingBlock {
+    label: String,
+    end: usize,
+    lines: Vec<(String, usize, bool)>,
+    has_body: bool,
+}
+
+fn block_kind(label: &str) -> &'static str {
+    if label.contains("PRIVATE KEY") {
+        "private-key"
+    } else if label.contains("CERTIFICATE REQUEST") {
+        "csr"
+    } else if label.contains("CERTIFICATE") {
+        "certificate"
+    } else if label.contains("CRL") {
+        "crl"
+    } else if label.starts_with("PGP") {
+        "pgp"
+    } else if label.contains("PUBLIC KEY") {
+        "public-key"
+    } else {
+        "other"
+    }
+}
+
+impl PendingBlock {
+    fn into_block(self, end_line: &str) -> PemBlock {
+        let offset = self.lines[0].1;
+        let mut lines: Vec<&str> = self.lines.iter().map(|(line, _, _)| line.trim()).collect();
+        lines.push(end_line.trim());
+        let encrypted = self.label.starts_with("ENCRYPTED") || lines.iter().any(|line| line.starts_with("Proc-Type: 4,ENCRYPTED"));
+        PemBlock { offset, text: lines.join("\n"), kind: block_kind(&self.label), label: self.label, encrypted }
+    }
+}
+
+// Stitches consecutive lines back into PEM blocks (keys, certificates, CSRs, PGP armor).
+// Strings are otherwise classified one line at a time, so everything outside a block, and
+// the lines of a block that never reaches its END line, is passed through unchanged.
+#[derive(Default)]
+pub struct PemCollector {
+    pending: Option<PendingBlock>,
+}
+
+impl PemCollector {
+    // Feed the next line of a source along with the byte range it came from
+    pub fn feed(&mut self, text: &str, offset: usize, end: usize, wide: bool) -> Vec<PemItem> {
+        let line = text.trim();
+        let mut items = Vec::new();
+
+        if let Some(pending) = &mut self.pending {
+            if offset <= pending.end + MAX_LINE_GAP && pending.lines.len() < MAX_BLOCK_LINES {
+                if pending.has_body && line.strip_prefix("-----END ").and_then(|l| l.strip_suffix("-----")) == Some(pending.label.as_str()) {
+                    let block = self.pending.take().unwrap().into_block(line);
+                    items.push(PemItem::Block(block));
+                    return items;
+                }
+                if BODY_REGEX.is_match(line) {
+                    pending.has_body |= !line.is_empty() && !line.contains(": ");
+                    pending.lines.push((text.to_string(), offset, wide));
+                    pending.end = end;
+                    return items;
+                }
+            }
+            items.extend(self.finish());
+        }
+
+        match BEGIN_REGEX.captures(line) {
+            Some(caps) => {
+                self.pending = Some(PendingBlock {
+                    label: caps[1].to_string(),
+                    end,
+                    lines: vec![(text.to_string(), offset, wide)],
+                    has_body: false,
+                });
+            }
+            None => items.push(PemItem::Line(text.to_string(), offset, wide)),
+        }
+        items
+    }
+
+    // End of the source: hand back the lines of a block that was never closed
+    pub fn finish(&mut self) -> Vec<PemItem> {
+        self.pending
+            .take()
+            .map(|pending| pending.lines.into_iter().map(|(text, offset, wide)| PemItem::Line(text, offset, wide)).collect())
+            .unwrap_or_default()
+    }
+}
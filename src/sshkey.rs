@@ -0,0 +1,54 @@
+use base64::Engine;
+use base64::engine::general_purpose::{STANDARD, STANDARD_NO_PAD};
+use sha2::{Digest, Sha256};
+use crate::Attributes;
+
+// Next length-prefixed string of the SSH wire format (RFC 4251)
+fn read_string<'a>(data: &'a [u8], pos: &mut usize) -> Option<&'a [u8]> {
+    let len = u32::from_be_bytes(data.get(*pos..*pos + 4)?.try_into().ok()?) as usize;
+    let value = data.get(*pos + 4..(*pos + 4).checked_add(len)?)?;
+    *pos += 4 + len;
+    Some(value)
+}
+
+fn mpint_bits(mpint: &[u8]) -> usize {
+    let digits: Vec<u8> = mpint.iter().copied().skip_while(|&b| b == 0).collect();
+    digits.first().map_or(0, |first| digits.len() * 8 - first.leading_zeros() as usize)
+}
+
+fn curve_bits(curve: &[u8]) -> Option<usize> {
+    match curve {
+        b"nistp256" => Some(256),
+        b"nistp384" => Some(384),
+        b"nistp521" => Some(521),
+        _ => None,
+    }
+}
+
+// Decode an OpenSSH public key ("ssh-ed25519 AAAA...") into what `ssh-keygen -l` shows:
+// the key type, its size in bits and the SHA256 fingerprint of the blob.
+// None if the blob doesn't decode or describes a different key type than its prefix.
+pub fn describe(key: &str) -> Option<Attributes> {
+    let (name, blob) = key.split_once(char::is_whitespace)?;
+    let blob = STANDARD.decode(blob.trim()).ok()?;
+    let mut pos = 0;
+    if read_string(&blob, &mut pos)? != name.as_bytes() {
+        return None;
+    }
+
+    let (key_type, bits) = match name {
+        "ssh-rsa" => {
+            let _exponent = read_string(&blob, &mut pos)?;
+            ("RSA", mpint_bits(read_string(&blob, &mut pos)?))
+        }
+        "ssh-dss" => ("DSA", mpint_bits(read_string(&blob, &mut pos)?)),
+        "ssh-ed25519" => ("ED25519", read_string(&blob, &mut pos)?.len() * 8),
+        "sk-ssh-ed25519@openssh.com" => ("ED25519-SK", read_string(&blob, &mut pos)?.len() * 8),
+        "sk-ecdsa-sha2-nistp256@openssh.com" => ("ECDSA-SK", curve_bits(read_string(&blob, &mut pos)?)?),
+        _ if name.starts_with("ecdsa-sha2-") => ("ECDSA", curve_bits(read_string(&blob, &mut pos)?)?),
+        _ => return None,
+    };
+
+    let fingerprint = format!("SHA256:{}", STANDARD_NO_PAD.encode(Sha256::digest(&blob)));
+    Some(vec![("type", key_type.to_string()), ("bits", bits.to_string()), ("fingerprint", fingerprint)])
+}
@@ -0,0 +1,175 @@
+use regex::Regex;
+use once_cell::sync::{Lazy, OnceCell};
+use url::Url;
+use crate::Attributes;
+
+// Built-in scheme catalogue: scheme -> category. Anything not listed is "custom".
+const SCHEME_CATEGORIES: &[(&str, &str)] = &[
+    ("http", "web"), ("https", "web"), ("ftp", "web"), ("ftps", "web"),
+    ("ws", "websocket"), ("wss", "websocket"),
+    ("s3", "cloud-storage"), ("s3a", "cloud-storage"), ("s3n", "cloud-storage"), ("gs", "cloud-storage"),
+    ("az", "cloud-storage"), ("abfs", "cloud-storage"), ("abfss", "cloud-storage"), ("wasb", "cloud-storage"),
+    ("wasbs", "cloud-storage"), ("adl", "cloud-storage"), ("oss", "cloud-storage"), ("cos", "cloud-storage"),
+    ("file", "file"), ("hdfs", "file"),
+    ("ldap", "directory"), ("ldaps", "directory"),
+    ("smb", "file-share"), ("cifs", "file-share"), ("nfs", "file-share"), ("afp", "file-share"),
+    ("mongodb", "database"), ("mongodb+srv", "database"), ("redis", "database"), ("rediss", "database"),
+    ("postgres", "database"), ("postgresql", "database"), ("mysql", "database"), ("mariadb", "database"),
+    ("mssql", "database"), ("sqlserver", "database"), ("oracle", "database"), ("cassandra", "database"),
+    ("couchdb", "database"), ("clickhouse", "database"), ("neo4j", "database"), ("bolt", "database"),
+    ("memcached", "database"), ("elasticsearch", "database"), ("influxdb", "database"),
+    ("amqp", "message-queue"), ("amqps", "message-queue"), ("mqtt", "message-queue"), ("mqtts", "message-queue"),
+    ("kafka", "message-queue"), ("nats", "message-queue"), ("stomp", "message-queue"),
+    ("git", "vcs"), ("git+ssh", "vcs"), ("ssh+git", "vcs"), ("git+https", "vcs"), ("svn", "vcs"),
+    ("svn+ssh", "vcs"), ("hg", "vcs"),
+    ("ssh", "remote-access"), ("sftp", "remote-access"), ("scp", "remote-access"), ("telnet", "remote-access"),
+    ("rdp", "remote-access"), ("vnc", "remote-access"),
+];
+
+pub const CUSTOM_CATEGORY: &str = "custom";
+
+// scheme=category pairs from --url-scheme, checked before the built-in catalogue
+static SCHEME_OVERRIDES: OnceCell<Vec<(String, String)>> = OnceCell::new();
+
+// Install user supplied SCHEME=CATEGORY entries, returning the first malformed one
+pub fn configure_schemes(entries: &[String]) -> Result<(), String> {
+    let mut overrides = Vec::new();
+    for entry in entries {
+        match entry.split_once('=') {
+            Some((scheme, category)) if !scheme.is_empty() && !category.is_empty() => {
+                overrides.push((scheme.to_ascii_lowercase(), category.to_string()));
+            }
+            _ => return Err(format!("invalid --url-scheme '{}', expected SCHEME=CATEGORY", entry)),
+        }
+    }
+    let _ = SCHEME_OVERRIDES.set(overrides);
+    Ok(())
+}
+
+pub fn scheme_category(scheme: &str) -> String {
+    let overrides = SCHEME_OVERRIDES.get().map(Vec::as_slice).unwrap_or_default();
+    if let Some((_, category)) = overrides.iter().find(|(s, _)| s == scheme) {
+        return category.clone();
+    }
+    SCHEME_CATEGORIES
+        .iter()
+        .find(|(s, _)| *s == scheme)
+        .map_or(CUSTOM_CATEGORY, |(_, category)| category)
+        .to_string()
+}
+
+// Query parameter names whose values are credentials (api_key, access_token, X-Amz-Signature, ...)
+static SECRET_PARAM_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?i)^(?:.*[_.-])?(?:api[_-]?key|apikey|key|token|secret|password|passwd|pwd|sig|signature|auth|credential|session(?:id)?|jwt)$").unwrap());
+
+// A URL cut down to what actually belongs to it, split into its parts
+pub struct ParsedUrl {
+    pub text: String,
+    pub attrs: Attributes,
+    // (parameter name, decoded value, offset of the raw value within `text`)
+    pub secret_params: Vec<(String, String, usize)>,
+}
+
+// Strip what the greedy `scheme://\S+` match drags along: anything after a quote or angle
+// bracket (href="...">), trailing punctuation and closing brackets that were never opened
+pub fn trim_url(raw: &str) -> &str {
+    let mut url = match raw.find(['"', '\'', '<', '>', '`']) {
+        Some(end) => &raw[..end],
+        None => raw,
+    };
+
+    loop {
+        let Some(last) = url.chars().next_back() else {
+            return url;
+        };
+        let unbalanced = match last {
+            ')' => url.matches(')').count() > url.matches('(').count(),
+            ']' => url.matches(']').count() > url.matches('[').count(),
+            '}' => url.matches('}').count() > url.matches('{').count(),
+            '.' | ',' | ';' | ':' | '!' | '?' | '*' => true,
+            _ => false,
+        };
+        if !unbalanced {
+            return url;
+        }
+        url = &url[..url.len() - 1];
+    }
+}
+
+// Walks the raw query of `text` (after '?', up to '#') rather than the parsed one, which is
+// percent-encoded and so doesn't line up with `text` once it holds non-ASCII characters
+fn secret_params(text: &str) -> Vec<(String, String, usize)> {
+    let end = text.find('#').unwrap_or(text.len());
+    let Some(query_start) = text[..end].find('?').map(|i| i + 1) else {
+        return Vec::new();
+    };
+
+    let mut params = Vec::new();
+    let mut offset = query_start;
+    for pair in text[query_start..end].split('&') {
+        if let Some((name, value)) = url::form_urlencoded::parse(pair.as_bytes()).next()
+            && !value.is_empty()
+            && SECRET_PARAM_REGEX.is_match(&name)
+        {
+            let value_offset = offset + pair.find('=').map_or(0, |i| i + 1);
+            params.push((name.into_owned(), value.into_owned(), value_offset));
+        }
+        offset += pair.len() + 1;
+    }
+    params
+}
+
+// Trim and parse a URL candidate, returning None if what's left isn't a valid URL
+pub fn parse_url(raw: &str) -> Option<ParsedUrl> {
+    let text = trim_url(raw);
+    let url = Url::parse(text).ok()?;
+    if url.cannot_be_a_base() {
+        return None;
+    }
+
+    let mut attrs: Attributes = vec![
+        ("scheme", url.scheme().to_string()),
+        ("category", scheme_category(url.scheme())),
+    ];
+    if let Some(host) = url.host_str() {
+        attrs.push(("host", host.to_string()));
+    }
+    if let Some(port) = url.port_or_known_default() {
+        attrs.push(("port", port.to_string()));
+    }
+    if !url.path().is_empty() && url.path() != "/" {
+        attrs.push(("path", url.path().to_string()));
+    }
+    if let Some(query) = url.query() {
+        attrs.push(("query", query.to_string()));
+    }
+
+    Some(ParsedUrl { text: text.to_string(), secret_params: secret_params(text), attrs })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn secret_params_of(text: &str) -> Vec<(String, String, usize)> {
+        parse_url(text).unwrap().secret_params
+    }
+
+    #[test]
+    fn secret_param_offset() {
+        let text = "https://a.com/?x=1&token=abc#frag";
+        assert_eq!(secret_params_of(text), vec![("token".to_string(), "abc".to_string(), 25)]);
+    }
+
+    #[test]
+    fn secret_param_offset_after_non_ascii() {
+        let text = "https://a.com/?x=€€€€&token=abc";
+        let params = secret_params_of(text);
+        assert_eq!(params, vec![("token".to_string(), "abc".to_string(), 36)]);
+        assert_eq!(&text[36..], "abc");
+    }
+
+    #[test]
+    fn secret_param_in_fragment_ignored() {
+        assert!(secret_params_of("https://a.com/#x?token=abc").is_empty());
+    }
+}
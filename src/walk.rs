@@ -0,0 +1,78 @@
+use std::path::{Path, PathBuf};
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use walkdir::WalkDir;
+
+// Decides which of the files found under a directory get scanned
+pub struct FileFilter {
+    include: Option<GlobSet>,
+    exclude: GlobSet,
+}
+
+fn build_glob_set(patterns: &[String]) -> Result<GlobSet, globset::Error> {
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns {
+        builder.add(Glob::new(pattern)?);
+    }
+    builder.build()
+}
+
+impl FileFilter {
+    pub fn new(include: &[String], exclude: &[String]) -> Result<Self, globset::Error> {
+        let include = if include.is_empty() { None } else { Some(build_glob_set(include)?) };
+        Ok(FileFilter { include, exclude: build_glob_set(exclude)? })
+    }
+
+    // Globs are tried against both the path relative to the walked root and the bare
+    // file name, so `*.so` and `lib/**/*.so` both do what you'd expect
+    fn matches(&self, relative: &Path) -> bool {
+        let name = relative.file_name().map(Path::new).unwrap_or(relative);
+        let hit = |set: &GlobSet| set.is_match(relative) || set.is_match(name);
+
+        if hit(&self.exclude) {
+            return false;
+        }
+        self.include.as_ref().is_none_or(hit)
+    }
+}
+
+// Expand the command line inputs into the list of files to scan. Files named explicitly
+// are always kept; directories are only walked with `recursive`, and symlinks found while
+// walking are skipped unless `follow_symlinks` is set. Also returns whether any input
+// couldn't be scanned, i.e. a directory without `recursive` or an error while walking.
+pub fn collect_files(inputs: &[PathBuf], recursive: bool, follow_symlinks: bool, filter: &FileFilter) -> (Vec<PathBuf>, bool) {
+    let mut files = Vec::new();
+    let mut failed = false;
+
+    for input in inputs {
+        if !input.is_dir() {
+            files.push(input.clone());
+            continue;
+        }
+        if !recursive {
+            eprintln!("Error: {}: is a directory (use -r to scan it)", input.display());
+            failed = true;
+            continue;
+        }
+
+        for entry in WalkDir::new(input).follow_links(follow_symlinks).sort_by_file_name() {
+            let entry = match entry {
+                Ok(entry) => entry,
+                Err(e) => {
+                    eprintln!("Error: {}", e);
+                    failed = true;
+                    continue;
+                }
+            };
+            if !entry.file_type().is_file() {
+                continue;
+            }
+
+            let relative = entry.path().strip_prefix(input).unwrap_or(entry.path());
+            if filter.matches(relative) {
+                files.push(entry.into_path());
+            }
+        }
+    }
+
+    (files, failed)
+}